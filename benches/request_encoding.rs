@@ -0,0 +1,86 @@
+//! Compares allocations and time spent encoding requests into a fresh `Vec` for every request
+//! (`write_to_bytes`) and into the buffer reused between requests, as `API` does.
+//!
+//! Run with `cargo bench --bench request_encoding`.
+
+use bytes::{BufMut, Bytes, BytesMut};
+use protobuf::Message;
+use sc2::{
+	IntoProto,
+	action::{Action, Target},
+	geometry::Point2,
+	ids::AbilityId,
+};
+use sc2_proto::sc2api::Request;
+use std::{
+	alloc::{GlobalAlloc, Layout, System},
+	hint::black_box,
+	sync::atomic::{AtomicUsize, Ordering},
+	time::Instant,
+};
+
+const ITERATIONS: usize = 100_000;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+struct CountingAlloc;
+unsafe impl GlobalAlloc for CountingAlloc {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+		unsafe { System.alloc(layout) }
+	}
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		unsafe { System.dealloc(ptr, layout) }
+	}
+	unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+		ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+		unsafe { System.realloc(ptr, layout, new_size) }
+	}
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Typical step request: a few dozen unit commands.
+fn step_request() -> Request {
+	let mut req = Request::new();
+	let actions = &mut req.mut_action().actions;
+	for i in 0..32 {
+		let target = Target::Pos(Point2::new(i as f32, 64.0));
+		let action = Action::UnitCommand(AbilityId::Attack, target, vec![i, i + 1, i + 2], false);
+		actions.push(action.into_proto());
+	}
+	req
+}
+
+fn measure(name: &str, mut f: impl FnMut()) {
+	let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+	let start = Instant::now();
+	for _ in 0..ITERATIONS {
+		f();
+	}
+	let elapsed = start.elapsed();
+	let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+	println!(
+		"{name:<10} {:>8.1} ns/request, {:.2} allocations/request",
+		elapsed.as_nanos() as f64 / ITERATIONS as f64,
+		allocations as f64 / ITERATIONS as f64,
+	);
+}
+
+fn main() {
+	let req = step_request();
+
+	measure("fresh", || {
+		let data = Bytes::from(req.write_to_bytes().unwrap());
+		black_box(data);
+	});
+
+	let mut buf = BytesMut::new();
+	measure("reused", || {
+		buf.reserve(req.compute_size() as usize);
+		req.write_to_writer(&mut (&mut buf).writer()).unwrap();
+		let data = buf.split().freeze();
+		black_box(data);
+	});
+}
@@ -31,6 +31,21 @@ impl Player for EmptyBot {
 				if let Some(_u) = self.units.my.structures.get(tag) { /* your code here */ }
 			}
 			Event::RandomRaceDetected(_race) => { /* your code here */ }
+			Event::UpgradeComplete(_upgrade) => { /* your code here */ }
+			Event::UnitDamaged { tag, amount: _ } => {
+				if let Some(_u) = self.units.my.all.get(tag) { /* your code here */ }
+			}
+			Event::EnemyRevealed(tag) => {
+				if let Some(_u) = self.units.enemy.all.get(tag) { /* your code here */ }
+			}
+			Event::EnemyCloaked(tag) => {
+				if let Some(_u) = self.units.enemy.all.get(tag) { /* your code here */ }
+			}
+			Event::EnemySpotted(tag) => {
+				if let Some(_u) = self.units.enemy.all.get(tag) { /* your code here */ }
+			}
+			Event::EnemyLost(_tag) => { /* your code here */ }
+			Event::ActionError { .. } => { /* your code here */ }
 		}
 		Ok(())
 	}
@@ -175,7 +175,7 @@ def generate():
         ],
         [],
         [],
-        ["mod impls;"],
+        ["mod impls;", '#[cfg(feature = "serde")]', "pub mod numeric;"],
     ]
     enums_latest = parse_data(
         load((Path.home() / "Documents" / "StarCraft II" / "stableid.json").open())
@@ -1,10 +1,15 @@
 use proc_macro::TokenStream;
 use quote::{format_ident, quote};
-use regex::Regex;
-use syn::{Attribute, Data, DeriveInput, Fields, ItemEnum, ItemFn, ItemStruct, parse_macro_input};
+use syn::{
+	Data, DeriveInput, Expr, Fields, Ident, ItemEnum, ItemFn, ItemStruct, LitStr, Meta, ReturnType, Stmt,
+	Token, Type, parse_macro_input, parse_quote, punctuated::Punctuated,
+};
 
 #[proc_macro_attribute]
-pub fn bot(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn bot(attr: TokenStream, item: TokenStream) -> TokenStream {
+	let attr_parser = syn::meta::parser(|meta| Err(meta.error("#[bot] doesn't take any options")));
+	parse_macro_input!(attr with attr_parser);
+
 	let item = parse_macro_input!(item as ItemStruct);
 
 	let name = item.ident;
@@ -45,45 +50,101 @@ pub fn bot(_attr: TokenStream, item: TokenStream) -> TokenStream {
 
 #[proc_macro_attribute]
 pub fn bot_new(_attr: TokenStream, item: TokenStream) -> TokenStream {
-	let item = parse_macro_input!(item as ItemFn);
-
-	let vis = item.vis;
-	let signature = item.sig;
+	let mut item = parse_macro_input!(item as ItemFn);
 
 	// In syn v2, Stmt::Expr carries an optional semicolon: `Stmt::Expr(Expr, Option<Semi>)`. :contentReference[oaicite:1]{index=1}
-	let blocks = item.block.stmts.iter().map(|s| {
-		if let syn::Stmt::Expr(expr, semi) = s {
-			if let syn::Expr::Struct(struct_expr) = expr {
-				let path = &struct_expr.path;
-				// In syn v2, `ExprStruct.rest` is `Option<(Token![..], Expr)>`. :contentReference[oaicite:2]{index=2}
-				let rest = struct_expr.rest.as_ref().map(|e| quote! { ..#e });
-
-				let fields = struct_expr.fields.iter();
-
-				let body = quote! {
-					#path {
-						_bot: Default::default(),
-						#(#fields, )*
-						#rest
-					}
-				};
+	// Unit struct can be returned either as `Self` or by its name, taken from return type
+	let struct_name = match &item.sig.output {
+		ReturnType::Type(_, ty) => match &**ty {
+			Type::Path(ty) if ty.qself.is_none() => ty.path.segments.last().map(|s| s.ident.clone()),
+			_ => None,
+		},
+		ReturnType::Default => None,
+	};
+	let mut found = false;
+	for stmt in &mut item.block.stmts {
+		if let Stmt::Expr(expr, _) = stmt {
+			found |= inject_bot_field(expr, struct_name.as_ref());
+		}
+	}
 
-				return if semi.is_some() {
-					quote! { #body; }
-				} else {
-					quote! { #body }
-				};
-			}
+	if !found {
+		return syn::Error::new_spanned(
+			&item.sig,
+			"#[bot_new] constructor must return a struct literal (e.g. `Self { .. }`), \
+			 optionally wrapped in a block or `return`",
+		)
+		.to_compile_error()
+		.into();
+	}
+
+	TokenStream::from(quote! { #item })
+}
+
+/// Adds `_bot` field to the struct literal returned by given expression.
+/// Returns `false` if expression doesn't contain struct literal (or unit struct) in tail position.
+///
+/// Paths are treated as unit structs only when they are `Self` or `struct_name`,
+/// so other tail expressions like local variables are left untouched.
+fn inject_bot_field(expr: &mut Expr, struct_name: Option<&Ident>) -> bool {
+	match expr {
+		Expr::Struct(struct_expr) => {
+			struct_expr
+				.fields
+				.insert(0, parse_quote! { _bot: Default::default() });
+			true
+		}
+		// Unit struct, e.g. `MyBot`
+		Expr::Path(path)
+			if path.qself.is_none()
+				&& (path.path.is_ident("Self")
+					|| struct_name.is_some_and(|name| path.path.is_ident(name))) =>
+		{
+			let path = path.path.clone();
+			*expr = parse_quote! { #path { _bot: Default::default() } };
+			true
 		}
-		// Fallback: keep the original statement as-is.
-		quote! { #s }
-	});
+		Expr::Block(block) => match block.block.stmts.last_mut() {
+			Some(Stmt::Expr(expr, None)) => inject_bot_field(expr, struct_name),
+			_ => false,
+		},
+		Expr::Return(ret) => ret
+			.expr
+			.as_mut()
+			.is_some_and(|expr| inject_bot_field(expr, struct_name)),
+		Expr::Paren(paren) => inject_bot_field(&mut paren.expr, struct_name),
+		_ => false,
+	}
+}
 
-	TokenStream::from(quote! {
-		#vis #signature {
-			#(#blocks)*
+/// Converts `CamelCase` identifier to `snake_case`.
+///
+/// Runs of capitals are kept together as acronyms (`APMTracker` -> `apm_tracker`),
+/// and digits stay attached to the preceding word (`Level2Upgrade` -> `level2_upgrade`).
+fn to_snake_case(ident: &str) -> String {
+	let chars = ident.chars().collect::<Vec<_>>();
+	let mut result = String::with_capacity(ident.len() + 4);
+	for (i, &c) in chars.iter().enumerate() {
+		if c == '_' {
+			if !result.is_empty() && !result.ends_with('_') {
+				result.push('_');
+			}
+			continue;
 		}
-	})
+		if c.is_ascii_uppercase() && i > 0 {
+			let prev = chars[i - 1];
+			let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase());
+			// New word starts after lowercase or digit, or at the last capital of acronym followed by a word
+			let boundary = prev.is_ascii_lowercase()
+				|| prev.is_ascii_digit()
+				|| (prev.is_ascii_uppercase() && next_is_lower);
+			if boundary && !result.ends_with('_') {
+				result.push('_');
+			}
+		}
+		result.push(c.to_ascii_lowercase());
+	}
+	result
 }
 
 #[proc_macro_derive(FromStr, attributes(enum_from_str))]
@@ -91,25 +152,24 @@ pub fn enum_from_str_derive(input: TokenStream) -> TokenStream {
 	let item = parse_macro_input!(input as DeriveInput);
 	if let Data::Enum(data) = item.data {
 		let name = item.ident;
-		let variants = data.variants.iter().map(|v| &v.ident);
+		let variants = data.variants.iter().map(|v| &v.ident).collect::<Vec<_>>();
 
 		// `Attribute::parse_meta` and `NestedMeta` are gone in v2.
 		// Use `Attribute::parse_nested_meta` and `attr.path().is_ident(..)`. :contentReference[oaicite:3]{index=3}
-		let additional_attributes = |a: &Attribute| {
-			if a.path().is_ident("enum_from_str") {
-				let mut use_primitives = false;
-				let _ = a.parse_nested_meta(|meta| {
-					if meta.path.is_ident("use_primitives") {
-						use_primitives = true;
-					}
-					Ok(())
-				});
-				return use_primitives;
-			}
-			false
-		};
+		let mut use_primitives = false;
+		let mut case_insensitive = false;
+		for a in item.attrs.iter().filter(|a| a.path().is_ident("enum_from_str")) {
+			let _ = a.parse_nested_meta(|meta| {
+				if meta.path.is_ident("use_primitives") {
+					use_primitives = true;
+				} else if meta.path.is_ident("case_insensitive") {
+					case_insensitive = true;
+				}
+				Ok(())
+			});
+		}
 
-		let other_cases = if item.attrs.iter().any(additional_attributes) {
+		let other_cases = if use_primitives {
 			quote! {
 				n => {
 					if let Ok(num) = n.parse() {
@@ -124,13 +184,34 @@ pub fn enum_from_str_derive(input: TokenStream) -> TokenStream {
 			quote! { _ => return Err(sc2_macro::ParseEnumError) }
 		};
 
+		let (input, names) = if case_insensitive {
+			(
+				quote! { s.to_ascii_lowercase().as_str() },
+				variants
+					.iter()
+					.map(|v| {
+						let name = LitStr::new(&v.to_string().to_ascii_lowercase(), v.span());
+						quote! { #name }
+					})
+					.collect::<Vec<_>>(),
+			)
+		} else {
+			(
+				quote! { s },
+				variants
+					.iter()
+					.map(|v| quote! { stringify!(#v) })
+					.collect::<Vec<_>>(),
+			)
+		};
+
 		TokenStream::from(quote! {
 			impl std::str::FromStr for #name {
 				type Err = sc2_macro::ParseEnumError;
 				fn from_str(s: &str) -> Result<Self, Self::Err> {
-					Ok(match s {
+					Ok(match #input {
 						#(
-							stringify!(#variants) => Self::#variants,
+							#names => Self::#variants,
 						)*
 						#other_cases,
 					})
@@ -142,22 +223,99 @@ pub fn enum_from_str_derive(input: TokenStream) -> TokenStream {
 	}
 }
 
+#[proc_macro_derive(EnumDisplay, attributes(enum_display))]
+pub fn enum_display_derive(input: TokenStream) -> TokenStream {
+	let item = parse_macro_input!(input as DeriveInput);
+	if let Data::Enum(data) = item.data {
+		let name = item.ident;
+		let variants = data.variants.iter().map(|v| &v.ident);
+
+		let mut use_primitives = false;
+		for a in item.attrs.iter().filter(|a| a.path().is_ident("enum_display")) {
+			let parsed = a.parse_nested_meta(|meta| {
+				if meta.path.is_ident("use_primitives") {
+					use_primitives = true;
+					Ok(())
+				} else {
+					Err(meta.error("#[enum_display] only takes `use_primitives` option"))
+				}
+			});
+			if let Err(e) = parsed {
+				return e.to_compile_error().into();
+			}
+		}
+
+		// Discriminants are printed in type of enum representation, so they don't wrap
+		let repr = item
+			.attrs
+			.iter()
+			.filter(|a| a.path().is_ident("repr"))
+			.filter_map(|a| {
+				a.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+					.ok()
+			})
+			.flatten()
+			.find_map(|meta| match meta {
+				Meta::Path(path) => path.get_ident().filter(|i| is_int_type(i)).cloned(),
+				_ => None,
+			})
+			.unwrap_or_else(|| format_ident!("isize"));
+
+		let arms = variants.map(|v| {
+			if use_primitives {
+				quote! { Self::#v { .. } => write!(f, "{}", Self::#v as #repr) }
+			} else {
+				quote! { Self::#v { .. } => f.write_str(stringify!(#v)) }
+			}
+		});
+
+		TokenStream::from(quote! {
+			impl std::fmt::Display for #name {
+				fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+					match self {
+						#(#arms,)*
+					}
+				}
+			}
+		})
+	} else {
+		panic!("Can only derive EnumDisplay for enums")
+	}
+}
+
+fn is_int_type(ident: &Ident) -> bool {
+	const INT_TYPES: [&str; 12] = [
+		"i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+	];
+	INT_TYPES.iter().any(|t| ident == t)
+}
+
 #[proc_macro_attribute]
 pub fn variant_checkers(_attr: TokenStream, item: TokenStream) -> TokenStream {
 	let item = parse_macro_input!(item as ItemEnum);
 
 	let name = &item.ident;
-	let variants = item.variants.iter().map(|v| &v.ident);
-	let re = Regex::new(r"[A-Z0-9]{1}[a-z0-9]*").unwrap();
-	let snake_variants = variants.clone().map(|v| {
-		format_ident!(
-			"is_{}",
-			re.find_iter(&v.to_string())
-				.map(|m| m.as_str().to_ascii_lowercase())
-				.collect::<Vec<String>>()
-				.join("_")
-		)
-	});
+	let variants = item.variants.iter().map(|v| &v.ident).collect::<Vec<_>>();
+	let snake_variants = variants
+		.iter()
+		.map(|v| format_ident!("is_{}", to_snake_case(&v.to_string())));
+
+	// Listing variants is possible only when none of them has fields
+	let all_variants = item
+		.variants
+		.iter()
+		.all(|v| matches!(v.fields, Fields::Unit))
+		.then(|| {
+			quote! {
+				/// All variants of this enum.
+				pub const ALL: &'static [Self] = &[#(Self::#variants),*];
+				/// Iterates over all variants of this enum.
+				#[inline]
+				pub fn variants() -> impl Iterator<Item = Self> {
+					[#(Self::#variants),*].into_iter()
+				}
+			}
+		});
 
 	TokenStream::from(quote! {
 		#item
@@ -168,6 +326,40 @@ pub fn variant_checkers(_attr: TokenStream, item: TokenStream) -> TokenStream {
 					matches!(self, Self::#variants)
 				}
 			)*
+			#all_variants
 		}
 	})
 }
+
+#[cfg(test)]
+mod tests {
+	use super::to_snake_case;
+
+	#[test]
+	fn snake_case_of_camel_case() {
+		assert_eq!(to_snake_case("Idle"), "idle");
+		assert_eq!(to_snake_case("SiegeTank"), "siege_tank");
+	}
+
+	#[test]
+	fn snake_case_keeps_acronyms_together() {
+		assert_eq!(to_snake_case("APMTracker"), "apm_tracker");
+		assert_eq!(to_snake_case("HTTPServer"), "http_server");
+		assert_eq!(to_snake_case("ABC"), "abc");
+	}
+
+	#[test]
+	fn snake_case_attaches_digits_to_previous_word() {
+		assert_eq!(to_snake_case("Level2Upgrade"), "level2_upgrade");
+		assert_eq!(to_snake_case("Version10"), "version10");
+		assert_eq!(to_snake_case("MP3Player"), "mp3_player");
+	}
+
+	#[test]
+	fn snake_case_of_single_letter_segments() {
+		assert_eq!(to_snake_case("A"), "a");
+		assert_eq!(to_snake_case("AB"), "ab");
+		assert_eq!(to_snake_case("ATest"), "a_test");
+		assert_eq!(to_snake_case("TestA"), "test_a");
+	}
+}
@@ -0,0 +1,16 @@
+use sc2::{bot::Bot, prelude::*};
+
+#[bot]
+#[derive(Default)]
+struct DefaultBot {
+	count: u32,
+	name: String,
+}
+
+#[test]
+fn derives_default_with_bot_field() {
+	let bot = DefaultBot::default();
+	assert_eq!(bot.count, 0);
+	assert!(bot.name.is_empty());
+	let _: &Bot = &bot;
+}
@@ -0,0 +1,65 @@
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use sc2_macro::{EnumDisplay, FromStr, ParseEnumError};
+
+#[derive(Debug, PartialEq, FromStr)]
+enum Exact {
+	VeryEasy,
+	Hard,
+}
+
+#[derive(Debug, PartialEq, FromStr)]
+#[enum_from_str(case_insensitive)]
+enum CaseInsensitive {
+	VeryEasy,
+	Hard,
+}
+
+#[test]
+fn parses_exact_names() {
+	assert_eq!("VeryEasy".parse(), Ok(Exact::VeryEasy));
+	assert_eq!("Hard".parse(), Ok(Exact::Hard));
+	assert_eq!("hard".parse::<Exact>(), Err(ParseEnumError));
+}
+
+#[test]
+fn parses_mixed_case_names() {
+	assert_eq!("VeryEasy".parse(), Ok(CaseInsensitive::VeryEasy));
+	assert_eq!("veryEASY".parse(), Ok(CaseInsensitive::VeryEasy));
+	assert_eq!("HARD".parse(), Ok(CaseInsensitive::Hard));
+	assert_eq!("hard".parse(), Ok(CaseInsensitive::Hard));
+	assert_eq!("Medium".parse::<CaseInsensitive>(), Err(ParseEnumError));
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, FromStr, EnumDisplay)]
+enum Named {
+	VeryEasy,
+	Hard,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, FromPrimitive, FromStr, EnumDisplay)]
+#[enum_from_str(use_primitives)]
+#[enum_display(use_primitives)]
+#[repr(u64)]
+enum Numbered {
+	Small = 1,
+	Medium = 1 << 40,
+	Large = u64::MAX,
+}
+
+#[test]
+fn display_round_trips_names() {
+	for v in [Named::VeryEasy, Named::Hard] {
+		assert_eq!(v.to_string().parse(), Ok(v));
+	}
+	assert_eq!(Named::VeryEasy.to_string(), "VeryEasy");
+}
+
+#[test]
+fn display_round_trips_primitives() {
+	for v in [Numbered::Small, Numbered::Medium] {
+		assert_eq!(v.to_string().parse(), Ok(v));
+	}
+	assert_eq!(Numbered::Medium.to_string(), "1099511627776");
+	assert_eq!(Numbered::Large.to_string(), u64::MAX.to_string());
+}
@@ -1,58 +1,462 @@
 //! Contains nice wrapper around SC2 API.
 
 use crate::{
-	bot::{Locked, Rl},
+	FromProto,
+	bot::{Locked, Rl, Rs},
 	client::{SC2Result, WS},
 };
+use bytes::{BufMut, Bytes, BytesMut};
 use protobuf::Message;
-use sc2_proto::sc2api::{Request, Response};
-use tungstenite::Message::Binary;
+use sc2_proto::sc2api::{
+	Request, Response, ResponseData, ResponseGameInfo, ResponseObservation, Status as ProtoStatus,
+};
+use std::{
+	error::Error,
+	fmt, io, thread,
+	time::{Duration, Instant},
+};
+use tungstenite::{Message::Binary, client::connect, stream::MaybeTlsStream};
+
+/// Errors which may occur when communicating with SC2.
+///
+/// Returned boxed in [`SC2Result`], so it can be retrieved with `downcast_ref`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SC2Error {
+	/// Response wasn't received within timeout set by [`API::set_timeout`].
+	Timeout,
+	/// Connection was lost while sending request, which is not safe to retry.
+	Disconnected,
+	/// Response doesn't contain data of expected type, usually because request failed.
+	UnexpectedResponse,
+}
+impl fmt::Display for SC2Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			SC2Error::Timeout => write!(f, "SC2 didn't respond within timeout"),
+			SC2Error::Disconnected => write!(f, "Connection to SC2 was lost in the middle of the game"),
+			SC2Error::UnexpectedResponse => write!(f, "SC2 responded with unexpected response type"),
+		}
+	}
+}
+impl Error for SC2Error {}
+
+/// Status of SC2 reported in every response, see [`API::last_status`].
+#[variant_checkers]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameStatus {
+	/// Game is launched, but no game is created yet.
+	Launched,
+	/// Game is created and waits for players to join.
+	InitGame,
+	/// Game is in progress.
+	InGame,
+	/// Replay is in progress.
+	InReplay,
+	/// Game or replay has ended, but wasn't left yet.
+	Ended,
+	/// SC2 is shutting down.
+	Quit,
+	/// Status is not known.
+	Unknown,
+}
+impl GameStatus {
+	/// Checks if game is over and no more steps can be made, i.e. status is `Ended` or `Quit`.
+	#[inline]
+	pub fn is_finished(self) -> bool {
+		matches!(self, GameStatus::Ended | GameStatus::Quit)
+	}
+}
+impl FromProto<ProtoStatus> for GameStatus {
+	#[inline]
+	fn from_proto(status: ProtoStatus) -> Self {
+		match status {
+			ProtoStatus::launched => GameStatus::Launched,
+			ProtoStatus::init_game => GameStatus::InitGame,
+			ProtoStatus::in_game => GameStatus::InGame,
+			ProtoStatus::in_replay => GameStatus::InReplay,
+			ProtoStatus::ended => GameStatus::Ended,
+			ProtoStatus::quit => GameStatus::Quit,
+			ProtoStatus::unknown => GameStatus::Unknown,
+		}
+	}
+}
+
+fn into_error(e: tungstenite::Error) -> Box<dyn Error> {
+	match e {
+		tungstenite::Error::Io(e)
+			if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) =>
+		{
+			SC2Error::Timeout.into()
+		}
+		e => e.into(),
+	}
+}
+fn is_connection_lost(e: &tungstenite::Error) -> bool {
+	match e {
+		tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed => true,
+		tungstenite::Error::Io(e) => matches!(
+			e.kind(),
+			io::ErrorKind::ConnectionReset | io::ErrorKind::ConnectionAborted | io::ErrorKind::BrokenPipe
+		),
+		_ => false,
+	}
+}
+/// Only requests which don't change state of the game are safe to send again.
+fn is_retryable(req: &Request) -> bool {
+	req.has_ping() || req.has_available_maps() || req.has_game_info() || req.has_data()
+}
+/// Parses response directly from the payload of received message, without copying it into `Vec`.
+fn parse_response(msg: tungstenite::Message) -> SC2Result<Response> {
+	Ok(Response::parse_from_tokio_bytes(&msg.into_data())?)
+}
+/// Request is serialized once and its buffer is shared with the frame (and with retries)
+/// instead of being copied.
+fn exchange(ws: &mut WS, data: Bytes) -> tungstenite::Result<tungstenite::Message> {
+	ws.send(Binary(data))?;
+	ws.read()
+}
+/// Checks that response contains expected data, logging errors reported by SC2 otherwise.
+fn expect_response(res: &Response, has_expected: bool) -> SC2Result<()> {
+	if has_expected {
+		return Ok(());
+	}
+	for e in &res.error {
+		error!("SC2 responded with error: {}", e);
+	}
+	Err(SC2Error::UnexpectedResponse.into())
+}
+fn set_read_timeout(ws: &WS, dur: Option<Duration>) -> io::Result<()> {
+	match ws.get_ref() {
+		MaybeTlsStream::Plain(stream) => stream.set_read_timeout(dur),
+		_ => Err(io::Error::new(
+			io::ErrorKind::Unsupported,
+			"read timeout can be set only for plain TCP connection",
+		)),
+	}
+}
+
+#[derive(Default, Clone, Copy)]
+struct Settings {
+	timeout: Option<Duration>,
+	reconnect: Option<(u32, Duration)>,
+}
+
+#[derive(Default, Clone, Copy)]
+struct Latency {
+	last: Duration,
+	avg: Duration,
+}
+
+type Trace = Rs<dyn Fn(&Request, &Response) + Send + Sync>;
 
 /// SC2 API. Can be accessed through [`self.api()`](crate::bot::Bot::api).
-pub struct API(Rl<WS>);
+pub struct API {
+	ws: Rl<WS>,
+	url: String,
+	settings: Rl<Settings>,
+	trace: Rl<Option<Trace>>,
+	latency: Rl<Latency>,
+	status: Rl<Option<GameStatus>>,
+	buf: Rl<BytesMut>,
+}
 impl API {
-	pub(crate) fn new(ws: WS) -> API {
-		API(Rl::new(ws))
+	pub(crate) fn new(ws: WS, url: String) -> API {
+		API {
+			ws: Rl::new(ws),
+			url,
+			settings: Default::default(),
+			trace: Default::default(),
+			latency: Default::default(),
+			status: Default::default(),
+			buf: Default::default(),
+		}
 	}
 
-	/// Sends request and returns a response.
-	pub fn send(&self, req: Request) -> SC2Result<Response> {
-		let mut ws = self.0.write_lock();
+	/// Serializes request into the buffer reused between requests.
+	///
+	/// Returned bytes share the buffer, which is reclaimed on the next call
+	/// once the previous frame is sent and dropped, so no allocation is made in the common case.
+	fn encode(&self, req: &Request) -> SC2Result<Bytes> {
+		let mut buf = self.buf.write_lock();
+		buf.reserve(req.compute_size() as usize);
+		req.write_to_writer(&mut (&mut *buf).writer())?;
+		Ok(buf.split().freeze())
+	}
 
-		ws.send(Binary(req.write_to_bytes()?.into()))?;
+	/// Returns status of SC2 reported in the last received response,
+	/// or `None` if no response with status was received yet.
+	///
+	/// This is the reliable way to detect that game has ended or SC2 is quitting.
+	pub fn last_status(&self) -> Option<GameStatus> {
+		*self.status.read_lock()
+	}
+	/// Parses response and remembers status reported in it.
+	fn read_response(&self, msg: tungstenite::Message) -> SC2Result<Response> {
+		let res = parse_response(msg)?;
+		if res.has_status() {
+			*self.status.write_lock() = Some(GameStatus::from_proto(res.status()));
+		}
+		Ok(res)
+	}
 
-		let msg = ws.read()?;
+	/// Returns time between sending the last request and receiving its response.
+	pub fn last_latency(&self) -> Duration {
+		self.latency.read_lock().last
+	}
+	/// Returns exponential moving average of time between sending requests
+	/// and receiving responses, where each new measurement has weight of `0.1`.
+	pub fn avg_latency(&self) -> Duration {
+		self.latency.read_lock().avg
+	}
+	fn record_latency(&self, elapsed: Duration) {
+		let mut latency = self.latency.write_lock();
+		latency.avg = if latency.avg.is_zero() {
+			elapsed
+		} else {
+			latency.avg.mul_f64(0.9) + elapsed.mul_f64(0.1)
+		};
+		latency.last = elapsed;
+	}
 
-		let mut res = Response::new();
-		res.merge_from_bytes(&msg.into_data())?;
+	/// Sets callback, which is called with every request and its response
+	/// sent with [`send`](Self::send) or [`send_request`](Self::send_request).
+	pub fn set_trace(&self, f: impl Fn(&Request, &Response) + Send + Sync + 'static) {
+		*self.trace.write_lock() = Some(Rs::new(f));
+	}
+	/// Removes callback set by [`set_trace`](Self::set_trace).
+	pub fn clear_trace(&self) {
+		*self.trace.write_lock() = None;
+	}
+	/// Callback is taken out of the lock before calling it,
+	/// so it can set or clear trace itself without deadlock.
+	fn trace(&self) -> Option<Trace> {
+		self.trace.read_lock().clone()
+	}
+
+	/// Sets maximum time to wait for the response, `None` means waiting forever (default).
+	///
+	/// When time elapses, [`SC2Error::Timeout`] is returned.
+	/// Response may still come after that, so game should be considered unresponsive
+	/// and left instead of sending more requests.
+	pub fn set_timeout(&self, dur: Option<Duration>) -> SC2Result<()> {
+		set_read_timeout(&self.ws.read_lock(), dur)?;
+		self.settings.write_lock().timeout = dur;
+		Ok(())
+	}
+
+	/// Enables reconnection when connection to SC2 is lost while waiting for the response.
+	/// Reconnection is attempted up to `max_retries` times,
+	/// waiting `backoff` before the first attempt and twice as long before every next one.
+	///
+	/// Game state can't be restored after reconnection, so only requests which don't change it
+	/// (`ping`, `available_maps`, `game_info` and `data`) are sent again.
+	/// For other requests [`SC2Error::Disconnected`] is returned.
+	pub fn with_reconnect(&self, max_retries: u32, backoff: Duration) -> &Self {
+		self.settings.write_lock().reconnect = Some((max_retries, backoff));
+		self
+	}
+
+	fn exchange(&self, req: &Request) -> SC2Result<tungstenite::Message> {
+		let start = Instant::now();
+		let msg = self.exchange_with_reconnect(req)?;
+		self.record_latency(start.elapsed());
+		Ok(msg)
+	}
+	fn exchange_with_reconnect(&self, req: &Request) -> SC2Result<tungstenite::Message> {
+		let data = self.encode(req)?;
+		let err = match exchange(&mut self.ws.write_lock(), data.clone()) {
+			Err(e) if is_connection_lost(&e) => e,
+			res => return res.map_err(into_error),
+		};
+
+		let settings = *self.settings.read_lock();
+		let Some((max_retries, backoff)) = settings.reconnect else {
+			return Err(into_error(err));
+		};
+		if !is_retryable(req) {
+			error!("Lost connection to SC2 in the middle of the game: {}", err);
+			return Err(SC2Error::Disconnected.into());
+		}
+
+		for attempt in 0..max_retries {
+			let delay = backoff.saturating_mul(1 << attempt.min(16));
+			debug!("Connection to SC2 lost: {}; reconnecting in {:?}", err, delay);
+			thread::sleep(delay);
+
+			let mut ws = match connect(&self.url) {
+				Ok((ws, _)) => ws,
+				Err(e) => {
+					error!("Reconnect failed: {}", e);
+					continue;
+				}
+			};
+			set_read_timeout(&ws, settings.timeout)?;
+			let res = exchange(&mut ws, data.clone());
+			*self.ws.write_lock() = ws;
+			match res {
+				Err(e) if is_connection_lost(&e) => continue,
+				res => return res.map_err(into_error),
+			}
+		}
+
+		Err(into_error(err))
+	}
+
+	/// Sends request and returns a response.
+	pub fn send(&self, req: Request) -> SC2Result<Response> {
+		let msg = self.exchange(&req)?;
+
+		let res = self.read_response(msg)?;
+		if let Some(trace) = self.trace() {
+			trace(&req, &res);
+		}
 		Ok(res)
 	}
 
 	/// Sends request, waits for the response, but ignores it (useful when response is empty).
 	pub fn send_request(&self, req: Request) -> SC2Result<()> {
-		let mut ws = self.0.write_lock();
-		ws.send(Binary(req.write_to_bytes()?.into()))?;
-		let _ = ws.read()?;
+		let res = self.read_response(self.exchange(&req)?)?;
+		if let Some(trace) = self.trace() {
+			trace(&req, &res);
+		}
 		Ok(())
 	}
 
+	/// Requests information about the map and players.
+	pub fn request_game_info(&self) -> SC2Result<ResponseGameInfo> {
+		let mut req = Request::new();
+		req.mut_game_info();
+		let mut res = self.send(req)?;
+		expect_response(&res, res.has_game_info())?;
+		Ok(res.take_game_info())
+	}
+	/// Requests static data about all abilities, unit types, upgrades, buffs and effects.
+	pub fn request_data(&self) -> SC2Result<ResponseData> {
+		let mut req = Request::new();
+		let req_data = req.mut_data();
+		req_data.set_ability_id(true);
+		req_data.set_unit_type_id(true);
+		req_data.set_upgrade_id(true);
+		req_data.set_buff_id(true);
+		req_data.set_effect_id(true);
+		let mut res = self.send(req)?;
+		expect_response(&res, res.has_data())?;
+		Ok(res.take_data())
+	}
+	/// Requests observation of the current game state.
+	pub fn request_observation(&self) -> SC2Result<ResponseObservation> {
+		let mut req = Request::new();
+		req.mut_observation();
+		let mut res = self.send(req)?;
+		expect_response(&res, res.has_observation())?;
+		Ok(res.take_observation())
+	}
+
+	/// Leaves current game immediately, which is counted as Defeat (i.e. surrender).
+	///
+	/// Unlike [`Debugger::end_game`](crate::debug::Debugger::end_game), it doesn't use debug interface,
+	/// so it's the proper way to concede on ladder, where debug commands may be disabled or forbidden.
+	/// Usually [`Bot::leave`](crate::bot::Bot::leave) should be preferred, which leaves after the current step.
+	pub fn leave_game(&self) -> SC2Result<()> {
+		let mut req = Request::new();
+		req.mut_leave_game();
+		self.send_request(req)
+	}
+
+	/// Sends all requests at once, then waits for all responses and returns them in the same order.
+	///
+	/// SC2 processes requests sequentially and responds in order they were received,
+	/// so pipelining saves round trips when many independent requests (e.g. queries) are needed.
+	/// Connection is held for the whole exchange, so other requests can't get in between.
+	///
+	/// Batches aren't retried on reconnection and don't affect [latency](Self::last_latency) statistics.
+	pub fn send_batch(&self, reqs: Vec<Request>) -> SC2Result<Vec<Response>> {
+		let mut ws = self.ws.write_lock();
+		for req in &reqs {
+			ws.write(Binary(self.encode(req)?)).map_err(into_error)?;
+		}
+		ws.flush().map_err(into_error)?;
+
+		let mut responses = Vec::with_capacity(reqs.len());
+		for _ in &reqs {
+			responses.push(self.read_response(ws.read().map_err(into_error)?)?);
+		}
+		drop(ws);
+
+		if let Some(trace) = self.trace() {
+			for (req, res) in reqs.iter().zip(&responses) {
+				trace(req, res);
+			}
+		}
+		Ok(responses)
+	}
+
 	/// Sends request, but doesn't wait for the response (use only when more control required,
 	/// in common cases prefered to use [`send`] or [`send_request`]).
 	///
 	/// [`send`]: Self::send
 	/// [`send_request`]: Self::send_request
 	pub fn send_only(&self, req: Request) -> SC2Result<()> {
-		self.0.write_lock().send(Binary(req.write_to_bytes()?.into()))?;
+		let data = self.encode(&req)?;
+		self.ws.write_lock().send(Binary(data))?;
 		Ok(())
 	}
 	/// Waits for a response (useful only after [`send_only`]).
 	///
 	/// [`send_only`]: Self::send_only
 	pub fn wait_response(&self) -> SC2Result<Response> {
-		let msg = self.0.write_lock().read()?;
+		let msg = self.ws.write_lock().read().map_err(into_error)?;
+		self.read_response(msg)
+	}
+}
 
-		let mut res = Response::new();
-		res.merge_from_bytes(&msg.into_data())?;
-		Ok(res)
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::net::TcpListener;
+
+	/// Request for data of single kind: abilities if `abilities` is `true`, unit types otherwise.
+	fn data_request(abilities: bool) -> Request {
+		let mut req = Request::new();
+		let data = req.mut_data();
+		data.set_ability_id(abilities);
+		data.set_unit_type_id(!abilities);
+		req
+	}
+
+	#[test]
+	fn send_batch_keeps_order() {
+		let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+		let url = format!("ws://{}/sc2api", listener.local_addr().unwrap());
+
+		// Answers each request with data of requested kind, one request at a time like SC2
+		let server = thread::spawn(move || {
+			let mut ws = tungstenite::accept(listener.accept().unwrap().0).unwrap();
+			for _ in 0..2 {
+				let req = Request::parse_from_bytes(&ws.read().unwrap().into_data()).unwrap();
+				let mut res = Response::new();
+				let data = res.mut_data();
+				if req.data().ability_id() {
+					data.abilities.push(Default::default());
+				}
+				if req.data().unit_type_id() {
+					data.units.push(Default::default());
+				}
+				ws.send(Binary(res.write_to_bytes().unwrap().into())).unwrap();
+			}
+		});
+
+		let (ws, _) = connect(&url).unwrap();
+		let api = API::new(ws, url);
+		let responses = api
+			.send_batch(vec![data_request(true), data_request(false)])
+			.unwrap();
+		server.join().unwrap();
+
+		let kinds = responses
+			.iter()
+			.map(|res| (res.data().abilities.len(), res.data().units.len()))
+			.collect::<Vec<_>>();
+		assert_eq!(kinds, [(1, 0), (0, 1)]);
 	}
 }
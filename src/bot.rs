@@ -9,7 +9,7 @@ use crate::{
 	debug::{DebugCommand, Debugger},
 	distance::*,
 	game_data::{Cost, GameData},
-	game_info::GameInfo,
+	game_info::{GameInfo, group_resources},
 	game_state::Effect,
 	game_state::{Alliance, GameState},
 	geometry::{Point2, Point3},
@@ -18,7 +18,6 @@ use crate::{
 	ramp::{Ramp, Ramps},
 	unit::{DataForUnit, SharedUnitData, Unit},
 	units::{AllUnits, Units},
-	utils::{dbscan, range_query},
 };
 use indexmap::IndexSet;
 use num_traits::ToPrimitive;
@@ -391,6 +390,8 @@ pub struct Bot {
 	pub(crate) api: Option<API>,
 	pub(crate) game_step: Rs<LockU32>,
 	pub(crate) game_left: bool,
+	pub(crate) replay: bool,
+	pub(crate) leave_on_error: bool,
 	#[doc(hidden)]
 	pub disable_fog: bool,
 	/// Actual race of your bot.
@@ -465,6 +466,8 @@ pub struct Bot {
 	enemy_upgrades: Rw<FxHashSet<UpgradeId>>,
 	pub(crate) owned_tags: FxHashSet<u64>,
 	pub(crate) under_construction: FxHashSet<u64>,
+	pub(crate) invisible_enemies: FxHashMap<u64, bool>,
+	pub(crate) visible_enemies: FxHashSet<u64>,
 	pub(crate) available_frames: Rw<FxHashMap<u64, u32>>,
 }
 
@@ -851,41 +854,17 @@ impl Bot {
 
 		// Calculating expansion locations
 
-		const RESOURCE_SPREAD: f32 = 72.25; // 8.5
-
 		let all_resources = self
 			.units
 			.resources
 			.filter(|r| r.type_id() != UnitTypeId::MineralField450);
+		let (positions, tags): (Vec<Point2>, Vec<u64>) =
+			all_resources.iter().map(|r| (r.position(), r.tag())).unzip();
 
-		let positions = all_resources
-			.iter()
-			.map(|r| (r.position(), r.tag()))
-			.collect::<Vec<(Point2, u64)>>();
-
-		let resource_groups = dbscan(
-			&positions,
-			range_query(
-				&positions,
-				|(p1, _), (p2, _)| p1.distance_squared(*p2),
-				RESOURCE_SPREAD,
-			),
-			1,
-		)
-		.0;
-
-		const OFFSET: isize = 7;
-		let offsets = iproduct!(-OFFSET..=OFFSET, -OFFSET..=OFFSET)
-			.filter(|(x, y)| {
-				let d = x * x + y * y;
-				16 < d && d <= 64
-			})
-			.collect::<Vec<(isize, isize)>>();
-
-		let mut expansions = resource_groups
+		let mut expansions = group_resources(&positions)
 			.into_iter()
-			.map(|group| {
-				let resources = all_resources.find_tags(group.iter().map(|(_, tag)| tag));
+			.filter_map(|group| {
+				let resources = all_resources.find_tags(group.iter().map(|&i| &tags[i]));
 				let center = resources.center().unwrap().floor() + 0.5;
 
 				let (loc, center, alliance, base) = if center.is_closer(4.0, self.start_center) {
@@ -898,26 +877,11 @@ impl Bot {
 				} else if center.is_closer(4.0, self.enemy_start_center) {
 					(self.enemy_start, self.enemy_start_center, Alliance::Enemy, None)
 				} else {
-					let location = offsets
-						.iter()
-						.filter_map(|(x, y)| {
-							let pos = center.offset(*x as f32, *y as f32);
-							if self.is_placeable((pos.x as usize, pos.y as usize)) {
-								let mut distance_sum = 0_f32;
-								let far_enough = |r: &Unit| {
-									let dist = pos.distance_squared(r);
-									distance_sum += dist;
-									dist >= if r.is_geyser() { 49.0 } else { 36.0 }
-								};
-								if resources.iter().all(far_enough) {
-									return Some((pos, distance_sum));
-								}
-							}
-							None
-						})
-						.min_by(|(_, d1), (_, d2)| d1.partial_cmp(d2).unwrap())
-						.expect("Can't detect right position for expansion")
-						.0;
+					let positions = resources.iter().map(|r| r.position()).collect::<Vec<_>>();
+					let Some(location) = self.game_info.townhall_location(&positions, &[]) else {
+						warn!("Can't detect right position for expansion near {:?}", center);
+						return None;
+					};
 
 					(
 						location,
@@ -941,14 +905,14 @@ impl Bot {
 					dist(a).partial_cmp(&dist(b)).unwrap()
 				});
 
-				Expansion {
+				Some(Expansion {
 					loc,
 					center,
 					minerals,
 					geysers,
 					alliance,
 					base,
-				}
+				})
 			})
 			.collect::<Vec<_>>();
 
@@ -969,60 +933,8 @@ impl Bot {
 		self.expansions = expansions;
 
 		// Calclulating ramp locations
-		let mut ramp_points = FxHashSet::default();
-
-		let area = self.game_info.playable_area;
-		for pos in iproduct!(area.x0..area.x1, area.y0..area.y1) {
-			if !self.is_pathable(pos) || self.is_placeable(pos) {
-				continue;
-			}
-
-			let h = self.get_height(pos);
-			let (x, y) = pos;
-
-			let neighbors = [
-				(x + 1, y),
-				(x - 1, y),
-				(x, y + 1),
-				(x, y - 1),
-				(x + 1, y + 1),
-				(x - 1, y - 1),
-				(x + 1, y - 1),
-				(x - 1, y + 1),
-			];
-
-			if neighbors.iter().all(|p| self.get_height(*p) == h) {
-				self.vision_blockers.push(Point2::new(x as f32, y as f32));
-			} else {
-				ramp_points.insert(pos);
-			}
-		}
-
-		let ramps = dbscan(
-			&ramp_points,
-			|&(x, y)| {
-				[
-					(x + 1, y),
-					(x - 1, y),
-					(x, y + 1),
-					(x, y - 1),
-					(x + 1, y + 1),
-					(x - 1, y - 1),
-					(x + 1, y - 1),
-					(x - 1, y + 1),
-				]
-				.iter()
-				.filter(|n| ramp_points.contains(n))
-				.copied()
-				.collect()
-			},
-			1,
-		)
-		.0
-		.into_iter()
-		.filter(|ps| ps.len() >= 8)
-		.map(|ps| Ramp::new(ps, &self.game_info.terrain_height, self.start_location))
-		.collect::<Vec<Ramp>>();
+		let (ramps, vision_blockers) = self.game_info.find_ramps_and_vision_blockers(self.start_location);
+		self.vision_blockers.extend(vision_blockers);
 
 		let get_closest_ramp = |loc: Point2| {
 			let (loc_x, loc_y) = <(usize, usize)>::from(loc);
@@ -1742,9 +1654,13 @@ impl Bot {
 			.collect())
 	}
 
-	/// Leaves current game, which is counted as Defeat for bot.
+	/// Leaves current game after the current step, which is counted as Defeat for bot.
 	///
-	/// Note: [`on_end`] will not be called, if needed use [`debug.end_game`] instead.
+	/// This is the ladder-safe way to surrender, since it doesn't use debug interface.
+	/// Use [`API::leave_game`] to leave immediately.
+	///
+	/// Note: [`on_end`] will not be called, if needed use [`debug.end_game`] instead,
+	/// but only in local games, since debug commands aren't allowed on ladder.
 	///
 	/// [`on_end`]: crate::Player::on_end
 	/// [`debug.end_game`]: Debugger::end_game
@@ -1753,11 +1669,23 @@ impl Bot {
 		Ok(())
 	}
 
+	/// Makes bot leave the game cleanly on any error returned from its step, instead of stopping with it.
+	///
+	/// Useful on ladder, where [`run_ladder_game`](crate::client::run_ladder_game) has no launch options.
+	/// See [`StepError`](crate::client::StepError) for details.
+	pub fn set_leave_on_error(&mut self, val: bool) {
+		self.leave_on_error = val;
+	}
+	/// Checks if bot is observing a replay, started by [`run_replay`](crate::client::run_replay).
+	///
+	/// Replays are read-only, so all actions and debug commands are discarded.
+	pub fn is_replay(&self) -> bool {
+		self.replay
+	}
+
 	pub(crate) fn close_client(&mut self) {
 		if let Some(api) = &self.api {
-			let mut req = Request::new();
-			req.mut_leave_game();
-			if let Err(e) = api.send_request(req) {
+			if let Err(e) = api.leave_game() {
 				error!("Request LeaveGame failed: {}", e);
 			}
 
@@ -1781,6 +1709,8 @@ impl Default for Bot {
 		Self {
 			game_step: Rs::new(LockU32::new(1)),
 			game_left: false,
+			replay: false,
+			leave_on_error: false,
 			disable_fog: false,
 			race: Race::Random,
 			enemy_race: Race::Random,
@@ -1823,6 +1753,8 @@ impl Default for Bot {
 			enemy_upgrades: Default::default(),
 			owned_tags: Default::default(),
 			under_construction: Default::default(),
+			invisible_enemies: Default::default(),
+			visible_enemies: Default::default(),
 			enemies_ordered: Default::default(),
 			enemies_current: Default::default(),
 			saved_hallucinations: Default::default(),
@@ -5,13 +5,14 @@
 
 use crate::{
 	IntoProto, IntoSC2, Player, PlayerSettings,
-	api::API,
+	api::{API, GameStatus},
 	bot::{Bot, LockOwned, Rs},
+	game_data::GameData,
 	game_state::update_state,
 	paths::*,
-	player::Computer,
+	player::{Computer, GameResult},
 };
-use sc2_proto::sc2api::{PlayerSetup, PlayerType, PortSet, Request, RequestCreateGame, Status};
+use sc2_proto::sc2api::{InterfaceOptions, PlayerSetup, PlayerType, PortSet, Request, RequestCreateGame};
 use std::{
 	error::Error,
 	fmt,
@@ -19,6 +20,7 @@ use std::{
 	io::Write,
 	net::{TcpListener, TcpStream},
 	ops::{Deref, DerefMut},
+	path::Path,
 	process::{Child, Command},
 };
 use tungstenite::{WebSocket, client::connect, stream::MaybeTlsStream};
@@ -26,6 +28,41 @@ use tungstenite::{WebSocket, client::connect, stream::MaybeTlsStream};
 pub(crate) type WS = WebSocket<MaybeTlsStream<TcpStream>>;
 pub type SC2Result<T> = Result<T, Box<dyn Error>>;
 
+/// Error returned from [`on_start`], [`on_step`] or [`on_event`] to choose how runner handles it.
+///
+/// Should be returned boxed in [`SC2Result`] (e.g. with `?` or `.into()`).
+/// Other errors are handled as [`Fatal`], unless [`leave_on_error`] is enabled,
+/// then they're handled as [`Recoverable`].
+///
+/// [`on_start`]: Player::on_start
+/// [`on_step`]: Player::on_step
+/// [`on_event`]: Player::on_event
+/// [`Fatal`]: Self::Fatal
+/// [`Recoverable`]: Self::Recoverable
+/// [`leave_on_error`]: LaunchOptions::leave_on_error
+#[derive(Debug)]
+pub enum StepError {
+	/// Error is logged and bot leaves the game cleanly (counted as defeat),
+	/// so on ladder it doesn't look like a crash or disconnect. Runner then returns `Ok`.
+	Recoverable(Box<dyn Error>),
+	/// Runner stops and returns this error, tearing down the game.
+	Fatal(Box<dyn Error>),
+}
+impl fmt::Display for StepError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			StepError::Recoverable(e) | StepError::Fatal(e) => e.fmt(f),
+		}
+	}
+}
+impl Error for StepError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			StepError::Recoverable(e) | StepError::Fatal(e) => Some(e.as_ref()),
+		}
+	}
+}
+
 #[cfg(all(feature = "wine_sc2", not(target_os = "linux")))]
 compile_error!("Wine is only supported on linux");
 
@@ -120,57 +157,21 @@ where
 		debug!("Launching SC2 process");
 		self.bot.process = Some(launch_client(&self.sc2_path, port, self.sc2_version));
 		debug!("Connecting to websocket");
-		self.bot.api = Some(API::new(connect_to_websocket(HOST, port)?));
+		self.bot.api = Some(connect_to_websocket(HOST, port)?);
 		Ok(())
 	}
 
 	/// Runs requested game.
 	pub fn run_game(&mut self) -> SC2Result<()> {
 		let settings = self.bot.get_player_settings();
-		let api = self.bot.api();
-
-		debug!("Sending CreateGame request");
-		let mut req = Request::new();
-		let req_create_game = req.mut_create_game();
-
-		req_create_game
-			.mut_local_map()
-			.set_map_path(self.map_path.clone());
-		create_player_setup(&settings, req_create_game);
-		create_computer_setup(&self.computer, req_create_game);
-
-		req_create_game.set_realtime(self.realtime);
-
-		let res = api.send(req)?;
-		let res_create_game = res.create_game();
-		if res_create_game.has_error() {
-			let err = format!(
-				"{:?}: {}",
-				res_create_game.error(),
-				res_create_game.error_details()
-			);
-			error!("{}", err);
-			panic!("{}", err);
-		}
-
-		debug!("Sending JoinGame request");
-		let player_id = join_game(&settings, api, None)?;
-		self.bot.player_id = player_id;
-
-		set_static_data(self.bot)?;
-
-		debug!("Entered main loop");
-		play_first_step(self.bot, self.realtime)?;
-		let mut iteration = 0;
-		while play_step(self.bot, iteration, self.realtime)? {
-			iteration += 1;
-		}
-		debug!("Game finished");
-
-		if let Some(path) = &self.save_replay_as {
-			save_replay(self.bot.api(), path)?;
-		}
-		Ok(())
+		let setup = GameSetup {
+			map_path: self.map_path.clone(),
+			computers: vec![self.computer],
+			realtime: self.realtime,
+			..Default::default()
+		};
+		create_game(&setup, &settings, self.bot.api())?;
+		play_local_game(self.bot, self.realtime, self.save_replay_as)
 	}
 
 	/// Changes map to play on.
@@ -187,6 +188,87 @@ where
 	}
 }
 
+/// Configuration of local game with custom set of computer opponents,
+/// used in [`run_game_setup`].
+///
+/// Allows to play team games and FFA vs built-in AI, e.g. 2v2 with computer ally is:
+/// ```no_run
+/// # use sc2::{client::GameSetup, player::{Computer, Difficulty, Race}};
+/// let setup = GameSetup::new("AutomatonLE")
+/// 	.with_computer(Computer::new(Race::Terran, Difficulty::Hard, None))
+/// 	.with_computer(Computer::new(Race::Zerg, Difficulty::Hard, None))
+/// 	.with_computer(Computer::new(Race::Protoss, Difficulty::Hard, None));
+/// ```
+/// Teams are decided by the map, usually players are split in order of their setup,
+/// with bot always being the first.
+#[derive(Debug, Default, Clone)]
+pub struct GameSetup {
+	map_path: String,
+	computers: Vec<Computer>,
+	realtime: bool,
+	disable_fog: bool,
+	random_seed: Option<u32>,
+	save_replay_as: Option<String>,
+}
+impl GameSetup {
+	/// Constructs setup for given map without opponents.
+	///
+	/// # Panics
+	/// Panics if the map doesn't exist in maps directory.
+	pub fn new(map: &str) -> Self {
+		Self {
+			map_path: get_map_path(&get_path_to_sc2(), map),
+			..Default::default()
+		}
+	}
+	/// Adds computer opponent (or ally, depending on the map) to the game.
+	pub fn with_computer(mut self, computer: Computer) -> Self {
+		self.computers.push(computer);
+		self
+	}
+	/// Plays game in real time mode.
+	pub fn with_realtime(mut self, realtime: bool) -> Self {
+		self.realtime = realtime;
+		self
+	}
+	/// Disables fog of war for all players.
+	pub fn with_disable_fog(mut self, disable_fog: bool) -> Self {
+		self.disable_fog = disable_fog;
+		self
+	}
+	/// Sets random seed of the game, overrides [`random_seed`](PlayerSettings::random_seed) of bot.
+	pub fn with_seed(mut self, seed: u32) -> Self {
+		self.random_seed = Some(seed);
+		self
+	}
+	/// Saves replay after the game in given path.
+	pub fn with_replay(mut self, path: &str) -> Self {
+		self.save_replay_as = Some(path.to_string());
+		self
+	}
+
+	/// Constructs `CreateGame` request for this setup with given bot as the first player.
+	pub fn to_request(&self, settings: &PlayerSettings) -> Request {
+		let mut req = Request::new();
+		let req_create_game = req.mut_create_game();
+
+		req_create_game
+			.mut_local_map()
+			.set_map_path(self.map_path.clone());
+		create_player_setup(settings, req_create_game);
+		for computer in &self.computers {
+			create_computer_setup(computer, req_create_game);
+		}
+
+		req_create_game.set_realtime(self.realtime);
+		req_create_game.set_disable_fog(self.disable_fog);
+		if let Some(seed) = self.random_seed.or(settings.random_seed) {
+			req_create_game.set_random_seed(seed);
+		}
+		req
+	}
+}
+
 /// Runner for games vs Human.
 pub struct RunnerMulti<'a, B>
 where
@@ -244,9 +326,9 @@ where
 		self.bot.process = Some(launch_client(&self.sc2_path, port_bot, self.sc2_version));
 
 		debug!("Connecting to host websocket");
-		self.human.api = Some(API::new(connect_to_websocket(HOST, port_human)?));
+		self.human.api = Some(connect_to_websocket(HOST, port_human)?);
 		debug!("Connecting to client websocket");
-		self.bot.api = Some(API::new(connect_to_websocket(HOST, port_bot)?));
+		self.bot.api = Some(connect_to_websocket(HOST, port_bot)?);
 
 		Ok(())
 	}
@@ -266,6 +348,9 @@ where
 		create_player_setup(&self.human_settings, req_create_game);
 		create_player_setup(&bot_settings, req_create_game);
 		req_create_game.set_realtime(self.realtime);
+		if let Some(seed) = bot_settings.random_seed.or(self.human_settings.random_seed) {
+			req_create_game.set_random_seed(seed);
+		}
 
 		let res = human_api.send(req)?;
 		let res_create_game = res.create_game();
@@ -299,10 +384,11 @@ where
 		set_static_data(self.bot)?;
 
 		debug!("Entered main loop");
-		play_first_step(self.bot, self.realtime)?;
 		let mut iteration = 0;
-		while play_step(self.bot, iteration, self.realtime)? {
-			iteration += 1;
+		if play_first_step(self.bot, self.realtime)? {
+			while play_step(self.bot, iteration, self.realtime)? {
+				iteration += 1;
+			}
 		}
 		debug!("Game finished");
 
@@ -389,6 +475,29 @@ pub struct LaunchOptions<'a> {
 	pub save_replay_as: Option<&'a str>,
 	/// Play games in real time mode or not.
 	pub realtime: bool,
+	/// Number of game loops per step, otherwise [`game_step`](Bot::game_step) set by bot is used.
+	///
+	/// Ignored in real time mode, since game isn't stepped manually there.
+	pub step_size: Option<u32>,
+	/// Disable fog of war, so bot can see the whole map.
+	pub disable_fog: bool,
+	/// Leave the game cleanly on any error returned from bot's step, instead of stopping runner with it.
+	///
+	/// See [`StepError`] and [`Bot::set_leave_on_error`].
+	pub leave_on_error: bool,
+}
+impl LaunchOptions<'_> {
+	fn apply(&self, bot: &mut Bot) {
+		if let Some(step_size) = self.step_size {
+			bot.set_game_step(step_size);
+		}
+		if self.disable_fog {
+			bot.disable_fog = true;
+		}
+		if self.leave_on_error {
+			bot.leave_on_error = true;
+		}
+	}
 }
 
 // Runners
@@ -403,6 +512,7 @@ pub fn run_vs_computer<B>(
 where
 	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
 {
+	options.apply(bot);
 	let mut runner = RunnerSingle::new(bot, computer, map_name, options.sc2_version);
 	runner.launch()?;
 	runner.realtime = options.realtime;
@@ -411,6 +521,81 @@ where
 	Ok(())
 }
 
+/// Simple function to run local game with custom setup, see [`GameSetup`].
+pub fn run_game_setup<B>(bot: &mut B, setup: &GameSetup, sc2_version: Option<&str>) -> SC2Result<()>
+where
+	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
+{
+	debug!("Starting custom game");
+	let port = get_unused_port();
+	debug!("Launching SC2 process");
+	bot.process = Some(launch_client(&get_path_to_sc2(), port, sc2_version));
+	debug!("Connecting to websocket");
+	bot.api = Some(connect_to_websocket(HOST, port)?);
+
+	let settings = bot.get_player_settings();
+	create_game(setup, &settings, bot.api())?;
+	play_local_game(bot, setup.realtime, setup.save_replay_as.as_deref())
+}
+
+/// Runs bot over saved replay, observing it from the perspective of given player.
+///
+/// Observations are passed to bot the same way as in live game, so all events and [`on_step`]
+/// are called as usual. Replays are read-only, so actions and debug commands of bot are discarded.
+///
+/// Replay must be played on the same SC2 version it was recorded on, pass it in `sc2_version`
+/// if it's not the latest available.
+///
+/// [`on_step`]: Player::on_step
+pub fn run_replay<B>(
+	bot: &mut B,
+	path: &Path,
+	observed_player: u32,
+	sc2_version: Option<&str>,
+) -> SC2Result<()>
+where
+	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
+{
+	debug!("Starting replay");
+	let port = get_unused_port();
+	debug!("Launching SC2 process");
+	bot.process = Some(launch_client(&get_path_to_sc2(), port, sc2_version));
+	debug!("Connecting to websocket");
+	bot.api = Some(connect_to_websocket(HOST, port)?);
+
+	let settings = bot.get_player_settings();
+	let mut req = Request::new();
+	let req_start_replay = req.mut_start_replay();
+	req_start_replay.set_replay_path(path.canonicalize()?.to_string_lossy().into_owned());
+	req_start_replay.set_observed_player_id(observed_player);
+	req_start_replay.set_disable_fog(bot.disable_fog);
+	req_start_replay.set_realtime(false);
+	set_interface_options(&settings, req_start_replay.options.mut_or_insert_default());
+
+	debug!("Sending StartReplay request");
+	let res = bot.api().send(req)?;
+	let res_start_replay = res.start_replay();
+	if res_start_replay.has_error() {
+		let err = ProtoError::new(res_start_replay.error(), res_start_replay.error_details());
+		error!("{}", err);
+		return Err(Box::new(err));
+	}
+
+	bot.player_id = observed_player;
+	bot.replay = true;
+	set_static_data(bot)?;
+
+	debug!("Entered main loop");
+	let mut iteration = 0;
+	if play_first_step(bot, false)? {
+		while play_step(bot, iteration, false)? {
+			iteration += 1;
+		}
+	}
+	debug!("Replay finished");
+	Ok(())
+}
+
 /// Simple function to join ladder game.
 pub fn run_ladder_game<B>(
 	bot: &mut B,
@@ -425,7 +610,7 @@ where
 	debug!("Starting ladder game");
 
 	debug!("Connecting to websocket");
-	bot.api = Some(API::new(connect_to_websocket(host, port)?));
+	bot.api = Some(connect_to_websocket(host, port)?);
 
 	debug!("Sending JoinGame request");
 
@@ -449,9 +634,10 @@ where
 	debug!("Entered main loop");
 	// Main loop
 	let mut iteration = 0;
-	play_first_step(bot, false)?;
-	while play_step(bot, iteration, false)? {
-		iteration += 1;
+	if play_first_step(bot, false)? {
+		while play_step(bot, iteration, false)? {
+			iteration += 1;
+		}
 	}
 	debug!("Game finished");
 
@@ -468,6 +654,7 @@ pub fn run_vs_human<B>(
 where
 	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
 {
+	options.apply(bot);
 	let mut runner = RunnerMulti::new(bot, human_settings, map_name, options.sc2_version);
 	runner.launch()?;
 	runner.realtime = options.realtime;
@@ -501,21 +688,21 @@ fn set_static_data(bot: &mut Bot) -> SC2Result<()> {
 	let api = bot.api();
 
 	debug!("Requesting GameInfo");
-	let mut req = Request::new();
-	req.mut_game_info();
-	let mut res = api.send(req)?;
-	let game_info = res.take_game_info().into_sc2();
+	let game_info = api.request_game_info()?.into_sc2();
 
 	debug!("Requesting GameData");
-	let mut req = Request::new();
-	let req_game_data = req.mut_data();
-	req_game_data.set_ability_id(true);
-	req_game_data.set_unit_type_id(true);
-	req_game_data.set_upgrade_id(true);
-	req_game_data.set_buff_id(true);
-	req_game_data.set_effect_id(true);
-	let mut res = api.send(req)?;
-	let game_data = Rs::new(res.take_data().into_sc2());
+	let (game_data, report) = GameData::from_proto_with_report(api.request_data()?);
+	if !report.is_empty() {
+		debug!(
+			"Dropped unknown ids from GameData: {} abilities, {} units, {} upgrades, {} buffs, {} effects",
+			report.abilities.len(),
+			report.units.len(),
+			report.upgrades.len(),
+			report.buffs.len(),
+			report.effects.len(),
+		);
+	}
+	let game_data = Rs::new(game_data);
 
 	bot.game_info = game_info;
 	bot.game_data = game_data;
@@ -523,6 +710,45 @@ fn set_static_data(bot: &mut Bot) -> SC2Result<()> {
 	Ok(())
 }
 
+fn create_game(setup: &GameSetup, settings: &PlayerSettings, api: &API) -> SC2Result<()> {
+	debug!("Sending CreateGame request");
+	let res = api.send(setup.to_request(settings))?;
+	let res_create_game = res.create_game();
+	if res_create_game.has_error() {
+		let err = ProtoError::new(res_create_game.error(), res_create_game.error_details());
+		error!("{}", err);
+		return Err(Box::new(err));
+	}
+	Ok(())
+}
+
+/// Joins created local game and plays it until the end.
+fn play_local_game<B>(bot: &mut B, realtime: bool, save_replay_as: Option<&str>) -> SC2Result<()>
+where
+	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
+{
+	let settings = bot.get_player_settings();
+	debug!("Sending JoinGame request");
+	let player_id = join_game(&settings, bot.api(), None)?;
+	bot.player_id = player_id;
+
+	set_static_data(bot)?;
+
+	debug!("Entered main loop");
+	let mut iteration = 0;
+	if play_first_step(bot, realtime)? {
+		while play_step(bot, iteration, realtime)? {
+			iteration += 1;
+		}
+	}
+	debug!("Game finished");
+
+	if let Some(path) = save_replay_as {
+		save_replay(bot.api(), path)?;
+	}
+	Ok(())
+}
+
 fn create_player_setup(settings: &PlayerSettings, req_create_game: &mut RequestCreateGame) {
 	let mut setup = PlayerSetup::new();
 
@@ -556,16 +782,7 @@ fn join_game2(settings: &PlayerSettings, api: &API, ports: Option<&Ports>) -> SC
 
 	req_join_game.set_race(settings.race.into_proto());
 
-	let options = req_join_game.options.mut_or_insert_default();
-	options.set_raw(true);
-	options.set_score(true);
-	// options.mut_feature_layer()
-	// options.mut_render();
-	options.set_show_cloaked(true);
-	options.set_show_burrowed_shadows(true);
-	options.set_show_placeholders(true);
-	options.set_raw_affects_selection(settings.raw_affects_selection);
-	options.set_raw_crop_to_playable_area(settings.raw_crop_to_playable_area);
+	set_interface_options(settings, req_join_game.options.mut_or_insert_default());
 	if let Some(name) = &settings.name {
 		req_join_game.set_player_name(name.to_string());
 	}
@@ -588,6 +805,17 @@ fn join_game2(settings: &PlayerSettings, api: &API, ports: Option<&Ports>) -> SC
 	api.send_only(req)?;
 	Ok(())
 }
+fn set_interface_options(settings: &PlayerSettings, options: &mut InterfaceOptions) {
+	options.set_raw(true);
+	options.set_score(true);
+	// options.mut_feature_layer()
+	// options.mut_render();
+	options.set_show_cloaked(true);
+	options.set_show_burrowed_shadows(true);
+	options.set_show_placeholders(true);
+	options.set_raw_affects_selection(settings.raw_affects_selection);
+	options.set_raw_crop_to_playable_area(settings.raw_crop_to_playable_area);
+}
 fn wait_join(api: &API) -> SC2Result<u32> {
 	let res = api.wait_response()?;
 
@@ -601,7 +829,8 @@ fn wait_join(api: &API) -> SC2Result<u32> {
 	}
 }
 
-fn play_first_step<B>(bot: &mut B, realtime: bool) -> SC2Result<()>
+/// Returns `false` if bot left the game because of error, see [`play_step`].
+fn play_first_step<B>(bot: &mut B, realtime: bool) -> SC2Result<bool>
 where
 	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
 {
@@ -614,11 +843,22 @@ where
 	bot.prepare_start();
 	bot.prepare_step();
 
-	for e in events {
-		bot.on_event(e)?;
+	let result = events
+		.into_iter()
+		.try_for_each(|e| bot.on_event(e))
+		.and_then(|_| bot.on_start());
+	if let Err(e) = result {
+		if !should_leave_on(bot, &*e) {
+			return Err(e);
+		}
+		error!("Leaving the game because of error on start: {}", e);
+		bot.api().leave_game()?;
+		return Ok(false);
 	}
-	bot.on_start()?;
 
+	if bot.replay {
+		discard_commands(bot);
+	}
 	let bot_actions = bot.get_actions();
 	if !bot_actions.is_empty() {
 		let mut req = Request::new();
@@ -634,7 +874,7 @@ where
 		req.mut_step().set_count(bot.game_step.get_locked());
 		bot.api().send_request(req)?;
 	}
-	Ok(())
+	Ok(true)
 }
 
 fn play_step<B>(bot: &mut B, iteration: usize, realtime: bool) -> SC2Result<bool>
@@ -645,10 +885,14 @@ where
 	req.mut_observation().set_disable_fog(bot.disable_fog);
 	let res = bot.api().send(req)?;
 
-	if matches!(res.status(), Status::ended) {
-		let result = res.observation().player_result[bot.player_id as usize - 1]
-			.result()
-			.into_sc2();
+	if bot.api().last_status().is_some_and(GameStatus::is_finished) {
+		// Results may be missing for observed player in replays
+		let result = res
+			.observation()
+			.player_result
+			.iter()
+			.find(|r| r.player_id() == bot.player_id)
+			.map_or(GameResult::Undecided, |r| r.result().into_sc2());
 		debug!("Result for bot: {:?}", result);
 		bot.on_end(result)?;
 		return Ok(false);
@@ -657,17 +901,26 @@ where
 	let events = update_state(bot, res.observation())?;
 	bot.prepare_step();
 
-	for e in events {
-		bot.on_event(e)?;
+	let result = events
+		.into_iter()
+		.try_for_each(|e| bot.on_event(e))
+		.and_then(|_| bot.on_step(iteration));
+	if let Err(e) = result {
+		if !should_leave_on(bot, &*e) {
+			return Err(e);
+		}
+		error!("Leaving the game because of error on step {}: {}", iteration, e);
+		bot.api().leave_game()?;
+		return Ok(false);
 	}
-	bot.on_step(iteration)?;
 	if bot.game_left {
-		let mut req = Request::new();
-		req.mut_leave_game();
-		bot.api().send_request(req)?;
+		bot.api().leave_game()?;
 		return Ok(false);
 	}
 
+	if bot.replay {
+		discard_commands(bot);
+	}
 	let bot_actions = bot.get_actions();
 	if !bot_actions.is_empty() {
 		// println!("{:?}: {:?}", iteration, bot_actions);
@@ -703,6 +956,26 @@ where
 	Ok(true)
 }
 
+/// Checks if bot should leave the game because of given error returned from its step.
+fn should_leave_on(bot: &Bot, e: &(dyn Error + 'static)) -> bool {
+	match e.downcast_ref::<StepError>() {
+		Some(StepError::Recoverable(_)) => true,
+		Some(StepError::Fatal(_)) => false,
+		None => bot.leave_on_error,
+	}
+}
+
+/// Drops all actions and debug commands of bot, used in replays since they're read-only.
+fn discard_commands(bot: &mut Bot) {
+	let actions = bot.get_actions().len();
+	if actions > 0 {
+		warn!("Replays are read-only, discarded {} actions", actions);
+	}
+	bot.clear_actions();
+	bot.get_debug_commands();
+	bot.clear_debug_commands();
+}
+
 fn save_replay(api: &API, path: &str) -> SC2Result<()> {
 	let mut req = Request::new();
 	req.mut_save_replay();
@@ -765,14 +1038,14 @@ fn launch_client(sc2_path: &str, port: i32, sc2_version: Option<&str>) -> Child
 	process.spawn().expect("Can't launch SC2 process.")
 }
 
-fn connect_to_websocket(host: &str, port: i32) -> SC2Result<WS> {
+fn connect_to_websocket(host: &str, port: i32) -> SC2Result<API> {
 	use std::{thread, time::Duration};
 	let url = format!("ws://{}:{}/sc2api", host, port);
 
 	let mut backoff = Duration::from_millis(100);
 	loop {
 		match connect(&url) {
-			Ok((ws, _)) => return Ok(ws),
+			Ok((ws, _)) => return Ok(API::new(ws, url)),
 			Err(e) => {
 				error!("connect failed: {e}; retrying in {:?}", backoff);
 				thread::sleep(backoff);
@@ -4,6 +4,7 @@ use crate::{
 	IntoProto,
 	geometry::{Point2, Point3},
 	ids::UnitTypeId,
+	pixel_map::ByteMap,
 };
 use num_traits::ToPrimitive;
 use rustc_hash::FxHashSet;
@@ -14,9 +15,44 @@ use sc2_proto::debug::{
 	debug_set_unit_value::UnitValue as DebugSetUnitValue_UnitValue,
 };
 
-type Color = (u32, u32, u32);
+/// RGB color of debug drawings, each component is in range `0..=255`.
+pub type Color = (u32, u32, u32);
 type ScreenPos = (f32, f32);
 
+/// Linearly interpolates between colors `a` and `b`, `t` is clamped to `0..=1`.
+pub fn lerp_color(a: Color, b: Color, t: f32) -> Color {
+	let t = t.clamp(0.0, 1.0);
+	let lerp = |a: u32, b: u32| (a as f32 + (b as f32 - a as f32) * t).round() as u32;
+	(lerp(a.0, b.0), lerp(a.1, b.1), lerp(a.2, b.2))
+}
+/// Maps `value` in range `min..=max` to green → yellow → red gradient,
+/// values out of range are clamped.
+///
+/// Useful to visualize magnitudes, e.g. values of [`ThreatMap`](crate::threat_map::ThreatMap).
+pub fn scalar_color(value: f32, min: f32, max: f32) -> Color {
+	let t = if max > min {
+		(value - min) / (max - min)
+	} else {
+		0.0
+	};
+	if t < 0.5 {
+		lerp_color((0, 255, 0), (255, 255, 0), t * 2.0)
+	} else {
+		lerp_color((255, 255, 0), (255, 0, 0), t * 2.0 - 1.0)
+	}
+}
+
+/// Default font size of debug text.
+const DEFAULT_TEXT_SIZE: u32 = 8;
+/// Approximate size of one font pixel in world units with default camera.
+const TEXT_WORLD_SCALE: f32 = 0.03;
+/// Padding around text in boxes drawn by [`draw_text_boxed`](Debugger::draw_text_boxed).
+const TEXT_BOX_PADDING: f32 = 0.1;
+/// Number of nested boxes used to fill background of boxed text.
+const TEXT_BOX_DENSITY: u32 = 8;
+/// Background color of boxed text, dark to contrast with usually bright text.
+const TEXT_BOX_COLOR: Color = (0, 0, 0);
+
 /// Helper struct for interacting with Debug API.
 /// Can be accessed through [`debug`] field of bot.
 ///
@@ -52,6 +88,27 @@ impl Debugger {
 	pub fn draw_text_world(&mut self, text: &str, pos: Point3, color: Option<Color>, size: Option<u32>) {
 		self.draw_text(text, DebugPos::World(pos), color, size);
 	}
+	/// Draws text in game world over a filled dark box, which makes it easier to read over busy terrain.
+	///
+	/// Debug API can draw boxes only in game world, so there's no screen space version of this.
+	/// Size of the box is approximated from text length and font size, assuming default camera.
+	/// Box is filled with [`draw_box_filled`](Self::draw_box_filled).
+	pub fn draw_text_boxed(&mut self, text: &str, pos: Point3, color: Option<Color>, size: Option<u32>) {
+		let font = size.unwrap_or(DEFAULT_TEXT_SIZE) as f32 * TEXT_WORLD_SCALE;
+		let longest = text.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+		let lines = text.lines().count().max(1);
+
+		// Average glyph is about 0.6 of font size wide
+		let width = longest as f32 * font * 0.6 + TEXT_BOX_PADDING;
+		let height = lines as f32 * font + TEXT_BOX_PADDING;
+		self.draw_box_filled(
+			Point3::new(pos.x - TEXT_BOX_PADDING, pos.y - height, pos.z),
+			Point3::new(pos.x + width, pos.y + TEXT_BOX_PADDING, pos.z + 0.1),
+			TEXT_BOX_DENSITY,
+			Some(TEXT_BOX_COLOR),
+		);
+		self.draw_text_world(text, pos, color, size);
+	}
 	/// Draws text in game window with 2d coordinates, where (0, 0) is left upper corner.
 	pub fn draw_text_screen(
 		&mut self,
@@ -66,10 +123,47 @@ impl Debugger {
 	pub fn draw_line(&mut self, p0: Point3, p1: Point3, color: Option<Color>) {
 		self.debug_drawings.push(DebugDraw::Line(p0, p1, color));
 	}
+	/// Draws path in game world, connecting each pair of consecutive points with line.
+	///
+	/// Does nothing if there're less than 2 points.
+	pub fn draw_path(&mut self, points: &[Point3], color: Option<Color>) {
+		self.debug_drawings
+			.extend(points.windows(2).map(|w| DebugDraw::Line(w[0], w[1], color)));
+	}
+	/// Same as [`draw_path`](Self::draw_path), but lifts 2D points onto terrain of given height map.
+	pub fn draw_path_2d(&mut self, points: &[Point2], height: &ByteMap, color: Option<Color>) {
+		let points = points.iter().map(|p| p.to_ground(height)).collect::<Vec<_>>();
+		self.draw_path(&points, color);
+	}
 	/// Draws box in game world with corners `p0` and `p1`.
 	pub fn draw_box(&mut self, p0: Point3, p1: Point3, color: Option<Color>) {
 		self.debug_drawings.push(DebugDraw::Box(p0, p1, color));
 	}
+	/// Draws box in game world with `p0` and `p1` being its opposite corners,
+	/// making it look solid by filling it with nested boxes and diagonals of its top face.
+	///
+	/// API has no filled shapes, so `density` controls how many inner boxes are drawn:
+	/// `0` draws only outline with diagonals, higher values make box more visible.
+	/// Inner boxes are shrunk horizontally towards center and keep full height.
+	pub fn draw_box_filled(&mut self, p0: Point3, p1: Point3, density: u32, color: Option<Color>) {
+		let (x0, x1) = (p0.x.min(p1.x), p0.x.max(p1.x));
+		let (y0, y1) = (p0.y.min(p1.y), p0.y.max(p1.y));
+		let (z0, z1) = (p0.z.min(p1.z), p0.z.max(p1.z));
+		let (cx, cy) = ((x0 + x1) / 2.0, (y0 + y1) / 2.0);
+
+		self.draw_box(p0, p1, color);
+		for i in 1..=density {
+			let t = i as f32 / (density + 1) as f32;
+			let (hx, hy) = ((x1 - x0) / 2.0 * (1.0 - t), (y1 - y0) / 2.0 * (1.0 - t));
+			self.draw_box(
+				Point3::new(cx - hx, cy - hy, z0),
+				Point3::new(cx + hx, cy + hy, z1),
+				color,
+			);
+		}
+		self.draw_line(Point3::new(x0, y0, z1), Point3::new(x1, y1, z1), color);
+		self.draw_line(Point3::new(x0, y1, z1), Point3::new(x1, y0, z1), color);
+	}
 	/// Draws cube in game world with given half size of edge.
 	pub fn draw_cube(&mut self, pos: Point3, half_edge: f32, color: Option<Color>) {
 		let offset = Point3::new(half_edge, half_edge, half_edge);
@@ -111,6 +205,9 @@ impl Debugger {
 		self.debug_commands.push(DebugCommand::EndGame(true));
 	}
 	/// Ends game with Defeat for bot
+	///
+	/// Uses debug interface, so it's intended for local testing only.
+	/// To surrender on ladder use [`Bot::leave`](crate::bot::Bot::leave) instead.
 	pub fn end_game(&mut self) {
 		self.debug_commands.push(DebugCommand::EndGame(false));
 	}
@@ -35,6 +35,13 @@ pub trait Distance: Into<Point2> {
 	fn is_further<P: Into<Point2>>(self, distance: f32, other: P) -> bool {
 		self.distance_squared(other) > distance * distance
 	}
+	/// Checks if `other` is within given `distance` from `self` (inclusive).
+	///
+	/// Unlike [`is_closer`](Self::is_closer), points exactly on given distance are also counted.
+	#[inline]
+	fn within_distance<P: Into<Point2>>(self, other: P, distance: f32) -> bool {
+		self.distance_squared(other) <= distance * distance
+	}
 }
 
 impl<T: Into<Point2>> Distance for T {}
@@ -99,6 +106,21 @@ where
 		self.map(|u| u.distance_squared(target)).max_by(cmp)
 	}
 
+	/// Returns closest to `target` item in iterator together with distance to it.
+	fn closest_with_distance<T: Into<Point2>>(self, target: T) -> Option<(Self::Item, f32)> {
+		let target = target.into();
+		self.map(|u| (u, u.distance_squared(target)))
+			.min_by(|(_, a), (_, b)| cmp(a, b))
+			.map(|(u, dist)| (u, dist.sqrt()))
+	}
+	/// Returns furthest to `target` item in iterator together with distance to it.
+	fn furthest_with_distance<T: Into<Point2>>(self, target: T) -> Option<(Self::Item, f32)> {
+		let target = target.into();
+		self.map(|u| (u, u.distance_squared(target)))
+			.max_by(|(_, a), (_, b)| cmp(a, b))
+			.map(|(u, dist)| (u, dist.sqrt()))
+	}
+
 	/// Returns iterator of items sorted by distance to `target`.
 	///
 	/// This sort is stable (i.e., does not reorder equal elements) and `O(n * log(n))` worst-case.
@@ -49,6 +49,21 @@ where
 		self.map(|u| u.distance_squared(target)).max_by(cmp)
 	}
 
+	/// Returns closest to `target` item in iterator together with distance to it.
+	fn closest_with_distance<T: Into<Point2>>(self, target: T) -> Option<(Self::Item, f32)> {
+		let target = target.into();
+		self.map(|u| (u, u.distance_squared(target)))
+			.min_by(|(_, a), (_, b)| cmp(a, b))
+			.map(|(u, dist)| (u, dist.sqrt()))
+	}
+	/// Returns furthest to `target` item in iterator together with distance to it.
+	fn furthest_with_distance<T: Into<Point2>>(self, target: T) -> Option<(Self::Item, f32)> {
+		let target = target.into();
+		self.map(|u| (u, u.distance_squared(target)))
+			.max_by(|(_, a), (_, b)| cmp(a, b))
+			.map(|(u, dist)| (u, dist.sqrt()))
+	}
+
 	/// Returns iterator of items sorted by distance to `target`.
 	///
 	/// This sort is stable (i.e. does not reorder equal elements) and `O(n log n)` worst-case.
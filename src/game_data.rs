@@ -3,11 +3,14 @@
 
 use crate::{
 	FromProto, TryFromProto,
+	consts::{DAMAGE_BONUS_PER_UPGRADE, PRODUCERS, RACE_VALUES, TECH_REQUIREMENTS},
 	ids::{AbilityId, BuffId, EffectId, UnitTypeId, UpgradeId},
 	player::Race,
+	time::GameTime,
 };
 use num_traits::FromPrimitive;
-use rustc_hash::{FxBuildHasher, FxHashMap};
+use once_cell::sync::OnceCell;
+use rustc_hash::{FxBuildHasher, FxHashMap, FxHashSet};
 use sc2_proto::{
 	data::{
 		AbilityData as ProtoAbilityData, Attribute as ProtoAttribute, BuffData as ProtoBuffData,
@@ -18,6 +21,35 @@ use sc2_proto::{
 };
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+#[cfg(feature = "serde")]
+use std::{
+	fs::File,
+	io::{self, BufRead, BufReader, BufWriter, Write},
+	path::Path,
+};
+
+#[cfg(feature = "serde")]
+const CACHE_FORMAT: u32 = 1;
+
+/// Header written at the first line of [`GameData`] cache file.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize, PartialEq, Eq)]
+struct CacheHeader {
+	format: u32,
+	crate_version: String,
+	game_version: String,
+}
+#[cfg(feature = "serde")]
+impl CacheHeader {
+	fn new(game_version: &str) -> Self {
+		Self {
+			format: CACHE_FORMAT,
+			crate_version: env!("CARGO_PKG_VERSION").to_string(),
+			game_version: game_version.to_string(),
+		}
+	}
+}
 
 /// All the data about different ids stored here.
 /// Can be accessed through [`game_data`](crate::bot::Bot::game_data) field.
@@ -34,10 +66,275 @@ pub struct GameData {
 	pub buffs: FxHashMap<BuffId, BuffData>,
 	/// Information about effects mapped to `EffectId`s.
 	pub effects: FxHashMap<EffectId, EffectData>,
+	#[cfg_attr(feature = "serde", serde(skip))]
+	units_by_name: OnceCell<FxHashMap<String, UnitTypeId>>,
+	#[cfg_attr(feature = "serde", serde(skip))]
+	unlocked_by: OnceCell<FxHashMap<UnitTypeId, Vec<UnitTypeId>>>,
 }
-impl FromProto<ResponseData> for GameData {
-	#[inline]
-	fn from_proto(data: ResponseData) -> Self {
+impl GameData {
+	/// Returns id of unit type with given in-game name.
+	///
+	/// Matching is case-insensitive and ignores surrounding whitespace.
+	/// Index of names is built on first call and reused afterwards.
+	pub fn unit_by_name(&self, name: &str) -> Option<UnitTypeId> {
+		self.units_by_name
+			.get_or_init(|| {
+				let mut names = FxHashMap::with_capacity_and_hasher(self.units.len(), FxBuildHasher);
+				for data in self.units.values() {
+					names.entry(data.name.trim().to_lowercase()).or_insert(data.id);
+				}
+				names
+			})
+			.get(&name.trim().to_lowercase())
+			.copied()
+	}
+	/// Returns unit types whose [tech requirement](UnitTypeData::tech_requirement) is given building,
+	/// i.e. unit types which become available once it's finished. Result is sorted by id.
+	///
+	/// Upgrades aren't included, since API doesn't report their requirements.
+	/// Reverse index is built on first call and reused afterwards.
+	pub fn unlocked_by(&self, building: UnitTypeId) -> Vec<UnitTypeId> {
+		self.unlocked_by
+			.get_or_init(|| {
+				let mut index = FxHashMap::<UnitTypeId, Vec<UnitTypeId>>::default();
+				for data in self.units.values() {
+					if let Some(requirement) = data.tech_requirement {
+						index.entry(requirement).or_default().push(data.id);
+					}
+				}
+				for unlocked in index.values_mut() {
+					unlocked.sort_unstable_by_key(|&id| id as u32);
+				}
+				index
+			})
+			.get(&building)
+			.cloned()
+			.unwrap_or_default()
+	}
+	/// Iterates over available on current patch unit types of given race, excluding structures.
+	pub fn units_of_race(&self, race: Race) -> impl Iterator<Item = &UnitTypeData> {
+		self.available_of_race(race).filter(|data| !data.is_structure())
+	}
+	/// Iterates over available on current patch structure types of given race.
+	pub fn buildings_of_race(&self, race: Race) -> impl Iterator<Item = &UnitTypeData> {
+		self.available_of_race(race).filter(|data| data.is_structure())
+	}
+	fn available_of_race(&self, race: Race) -> impl Iterator<Item = &UnitTypeData> {
+		self.units
+			.values()
+			.filter(move |data| data.available && data.race == race)
+	}
+	/// Returns all prerequisites of given unit type in order they need to be obtained,
+	/// from the root (e.g. townhall) to the direct requirement.
+	///
+	/// Prerequisites are [tech requirements](UnitTypeData::tech_requirement) and [producers](PRODUCERS)
+	/// of unit type and of all its prerequisites. Each one is listed once,
+	/// after all of its own prerequisites. Larva isn't included, since it can't be produced.
+	pub fn tech_path(&self, unit: UnitTypeId) -> Vec<UnitTypeId> {
+		fn visit(
+			data: &GameData,
+			unit: UnitTypeId,
+			visited: &mut FxHashSet<UnitTypeId>,
+			path: &mut Vec<UnitTypeId>,
+		) {
+			let requirement = data
+				.units
+				.get(&unit)
+				.and_then(|data| data.tech_requirement)
+				.or_else(|| TECH_REQUIREMENTS.get(&unit).copied());
+			for prerequisite in [PRODUCERS.get(&unit).copied(), requirement].into_iter().flatten() {
+				if prerequisite == UnitTypeId::Larva || !visited.insert(prerequisite) {
+					continue;
+				}
+				visit(data, prerequisite, visited, path);
+				path.push(prerequisite);
+			}
+		}
+
+		let mut visited = FxHashSet::default();
+		visited.insert(unit);
+		let mut path = vec![];
+		visit(self, unit, &mut visited, &mut path);
+		path
+	}
+	/// Returns unit type which is consumed when given unit type is produced,
+	/// i.e. unit that must exist to morph into given one (e.g. `Larva` for `Roach`,
+	/// `Zergling` for `Baneling`, `Drone` for zerg buildings, `CommandCenter` for `OrbitalCommand`).
+	///
+	/// Returns `None` for units produced without consuming anything (e.g. `Marine` or `Queen`).
+	/// `Archon` isn't covered, since it's merged from two units of different types.
+	pub fn morph_prerequisite_unit(&self, unit: UnitTypeId) -> Option<UnitTypeId> {
+		let producer = PRODUCERS.get(&unit).copied()?;
+		match unit {
+			UnitTypeId::Baneling
+			| UnitTypeId::Ravager
+			| UnitTypeId::LurkerMP
+			| UnitTypeId::BroodLord
+			| UnitTypeId::Overseer
+			| UnitTypeId::OverlordTransport
+			| UnitTypeId::Lair
+			| UnitTypeId::Hive
+			| UnitTypeId::GreaterSpire
+			| UnitTypeId::OrbitalCommand
+			| UnitTypeId::PlanetaryFortress => Some(producer),
+			_ => matches!(producer, UnitTypeId::Larva | UnitTypeId::Drone).then_some(producer),
+		}
+	}
+	/// Returns cost of morphing into given unit type excluding cost of consumed unit,
+	/// e.g. `150` minerals for `OrbitalCommand` and `25/25` for `Baneling`.
+	///
+	/// API reports cumulative costs for morphs (`550` minerals for `OrbitalCommand`),
+	/// which is right for valuing units, but not for spending in build orders.
+	/// Returns full cost for units which aren't morphed, `None` if unit type is unknown.
+	pub fn morph_cost(&self, unit: UnitTypeId) -> Option<Cost> {
+		let mut cost = self.units.get(&unit)?.cost();
+		if let Some(prerequisite) = self
+			.morph_prerequisite_unit(unit)
+			.and_then(|id| self.units.get(&id))
+		{
+			let mut consumed = prerequisite.cost();
+			// Zerglings are reported with cost of the pair produced from single larva
+			if prerequisite.id == UnitTypeId::Zergling {
+				consumed.minerals /= 2;
+			}
+			cost.minerals = cost.minerals.saturating_sub(consumed.minerals);
+			cost.vespene = cost.vespene.saturating_sub(consumed.vespene);
+			cost.supply = (cost.supply - consumed.supply).max(0.0);
+		}
+		Some(cost)
+	}
+	/// Saves data to the cache file at given path, so it can be loaded with
+	/// [`load_from`](Self::load_from) instead of requesting it from the game again.
+	///
+	/// `game_version` is stored in the header of the file and should identify the game patch
+	/// data was received from (e.g. base build number).
+	#[cfg(feature = "serde")]
+	pub fn save_to(&self, path: &Path, game_version: &str) -> io::Result<()> {
+		let mut writer = BufWriter::new(File::create(path)?);
+		serde_json::to_writer(&mut writer, &CacheHeader::new(game_version))?;
+		writer.write_all(b"\n")?;
+		serde_json::to_writer(&mut writer, self)?;
+		writer.flush()
+	}
+	/// Loads data from the cache file saved by [`save_to`](Self::save_to).
+	///
+	/// Returns error of kind [`InvalidData`](io::ErrorKind::InvalidData) if cache was saved
+	/// with different `game_version` or by different version of this crate,
+	/// since ids may not match in that case.
+	#[cfg(feature = "serde")]
+	pub fn load_from(path: &Path, game_version: &str) -> io::Result<Self> {
+		let mut reader = BufReader::new(File::open(path)?);
+
+		let mut line = String::new();
+		reader.read_line(&mut line)?;
+		let header: CacheHeader = serde_json::from_str(&line)?;
+		let expected = CacheHeader::new(game_version);
+		if header != expected {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				format!(
+					"Stale GameData cache: saved for game version {:?} with format {} by sc2 {}, \
+					 expected game version {:?} with format {} by sc2 {}",
+					header.game_version,
+					header.format,
+					header.crate_version,
+					expected.game_version,
+					expected.format,
+					expected.crate_version,
+				),
+			));
+		}
+
+		Ok(serde_json::from_reader(reader)?)
+	}
+	/// Checks if given unit type is a worker of its race.
+	pub fn is_worker(&self, unit: UnitTypeId) -> bool {
+		self.units
+			.get(&unit)
+			.and_then(|data| RACE_VALUES.get(&data.race))
+			.is_some_and(|values| values.worker == unit)
+	}
+	/// Checks if given unit type is one of townhall forms of its race.
+	pub fn is_townhall(&self, unit: UnitTypeId) -> bool {
+		self.units
+			.get(&unit)
+			.and_then(|data| RACE_VALUES.get(&data.race))
+			.is_some_and(|values| values.townhalls.contains(&unit))
+	}
+	/// Returns canonical id of given ability, following [`remaps_to_ability_id`] to the root.
+	///
+	/// Returns given id if it isn't remapped or unknown.
+	///
+	/// [`remaps_to_ability_id`]: AbilityData::remaps_to_ability_id
+	pub fn canonical_ability(&self, id: AbilityId) -> AbilityId {
+		let mut id = id;
+		// Guard against cycles in broken data
+		for _ in 0..self.abilities.len() {
+			match self.abilities.get(&id).and_then(|data| data.remaps_to_ability_id) {
+				Some(next) if next != id => id = next,
+				_ => break,
+			}
+		}
+		id
+	}
+	/// Returns data of canonical ability for given id, see [`canonical_ability`](Self::canonical_ability).
+	pub fn resolve_ability(&self, id: AbilityId) -> Option<&AbilityData> {
+		self.abilities.get(&self.canonical_ability(id))
+	}
+	/// Checks if both abilities have the same canonical ability.
+	pub fn is_same_ability(&self, a: AbilityId, b: AbilityId) -> bool {
+		self.canonical_ability(a) == self.canonical_ability(b)
+	}
+	/// Returns half of the size of building created by given ability,
+	/// `None` if ability is unknown or doesn't create a building.
+	pub fn footprint(&self, ability: AbilityId) -> Option<f32> {
+		self.abilities
+			.get(&ability)
+			.filter(|data| data.is_building)
+			.and_then(|data| data.footprint_radius)
+	}
+	/// Returns size in tiles of building created by given ability (e.g. `3` for 3x3 buildings),
+	/// see [`footprint`](Self::footprint).
+	pub fn footprint_tiles(&self, ability: AbilityId) -> Option<usize> {
+		self.footprint(ability)
+			.map(|radius| (radius * 2.0).round() as usize)
+	}
+	/// Returns cast range of given ability,
+	/// `None` if ability is unknown or has no range (e.g. instant self-casts).
+	pub fn cast_range(&self, ability: AbilityId) -> Option<f32> {
+		self.abilities.get(&ability).and_then(|data| data.cast_range)
+	}
+	/// Returns cast range of given ability or `default` if it has none, see [`cast_range`](Self::cast_range).
+	pub fn cast_range_or(&self, ability: AbilityId, default: f32) -> f32 {
+		self.cast_range(ability).unwrap_or(default)
+	}
+}
+/// Raw ids of entries dropped while loading [`GameData`],
+/// because they don't match any known variant of corresponding id enum.
+///
+/// Non-empty report usually means that id enums are out of date with the game version.
+#[derive(Debug, Default, Clone)]
+pub struct DataLoadReport {
+	pub abilities: Vec<u32>,
+	pub units: Vec<u32>,
+	pub upgrades: Vec<u32>,
+	pub buffs: Vec<u32>,
+	pub effects: Vec<u32>,
+}
+impl DataLoadReport {
+	/// Total number of dropped entries.
+	pub fn len(&self) -> usize {
+		self.abilities.len() + self.units.len() + self.upgrades.len() + self.buffs.len() + self.effects.len()
+	}
+	/// Checks if nothing was dropped.
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
+
+impl GameData {
+	/// Same as converting from proto, but also returns report with raw ids of dropped entries.
+	pub fn from_proto_with_report(data: ResponseData) -> (Self, DataLoadReport) {
 		// Move out of ResponseData to avoid cloning
 		let abilities_vec = data.abilities;
 		let units_vec = data.units;
@@ -52,52 +349,173 @@ impl FromProto<ResponseData> for GameData {
 		let mut upgrades = FxHashMap::with_capacity_and_hasher(upgrades_vec.len(), hasher);
 		let mut buffs = FxHashMap::with_capacity_and_hasher(buffs_vec.len(), hasher);
 		let mut effects = FxHashMap::with_capacity_and_hasher(effects_vec.len(), hasher);
+		let mut report = DataLoadReport::default();
 
 		for a in abilities_vec.into_iter() {
-			if let Some(data) = AbilityData::try_from_proto(a) {
-				abilities.insert(data.id, data);
+			let raw = a.ability_id();
+			match AbilityData::try_from_proto(a) {
+				Some(data) => {
+					abilities.insert(data.id, data);
+				}
+				None => report.abilities.push(raw),
 			}
 		}
 		for u in units_vec.into_iter() {
-			if let Some(data) = UnitTypeData::try_from_proto(u) {
-				units.insert(data.id, data);
+			let raw = u.unit_id();
+			match UnitTypeData::try_from_proto(u) {
+				Some(data) => {
+					units.insert(data.id, data);
+				}
+				None => report.units.push(raw),
 			}
 		}
 		for up in upgrades_vec.into_iter() {
-			if let Some(data) = UpgradeData::try_from_proto(up) {
-				upgrades.insert(data.id, data);
+			let raw = up.upgrade_id();
+			match UpgradeData::try_from_proto(up) {
+				Some(data) => {
+					upgrades.insert(data.id, data);
+				}
+				None => report.upgrades.push(raw),
 			}
 		}
 		for b in buffs_vec.into_iter() {
-			if let Some(data) = BuffData::try_from_proto(b) {
-				buffs.insert(data.id, data);
+			let raw = b.buff_id();
+			match BuffData::try_from_proto(b) {
+				Some(data) => {
+					buffs.insert(data.id, data);
+				}
+				None => report.buffs.push(raw),
 			}
 		}
 		for e in effects_vec.into_iter() {
-			if let Some(data) = EffectData::try_from_proto(e) {
-				effects.insert(data.id, data);
+			let raw = e.effect_id();
+			match EffectData::try_from_proto(e) {
+				Some(data) => {
+					effects.insert(data.id, data);
+				}
+				None => report.effects.push(raw),
 			}
 		}
 
-		Self {
-			abilities,
-			units,
-			upgrades,
-			buffs,
-			effects,
-		}
+		(
+			Self {
+				abilities,
+				units,
+				upgrades,
+				buffs,
+				effects,
+				units_by_name: OnceCell::new(),
+				unlocked_by: OnceCell::new(),
+			},
+			report,
+		)
+	}
+}
+impl FromProto<ResponseData> for GameData {
+	#[inline]
+	fn from_proto(data: ResponseData) -> Self {
+		Self::from_proto_with_report(data).0
 	}
 }
 
 /// Cost of an item (`UnitTypeId` or `UpgradeId`) in resources, supply and time.
-#[derive(Debug, Default)]
+///
+/// Costs are ordered by total amount of resources (see [`resource_value`](Cost::resource_value)
+/// with gas weight of `1`), costs with equal total are ordered by minerals, vespene, supply and time.
+#[derive(Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Cost {
 	pub minerals: u32,
 	pub vespene: u32,
 	pub supply: f32,
+	/// Time in game loops, see [`GameTime`](crate::time::GameTime) for conversions.
 	pub time: f32,
 }
+impl Cost {
+	/// Checks if given amount of resources and free supply is enough to pay this cost.
+	///
+	/// Supply costs are multiples of `0.5`, which are represented exactly in `f32`,
+	/// so plain `>=` comparison is used.
+	pub fn can_afford(&self, minerals: u32, vespene: u32, supply_left: f32) -> bool {
+		minerals >= self.minerals && vespene >= self.vespene && supply_left >= self.supply
+	}
+	/// Returns amount of resources and supply which is not enough to pay this cost
+	/// (`0` in fields where given amount is sufficient).
+	///
+	/// `time` of returned cost is always `0`.
+	pub fn missing(&self, minerals: u32, vespene: u32, supply_left: f32) -> Cost {
+		Cost {
+			minerals: self.minerals.saturating_sub(minerals),
+			vespene: self.vespene.saturating_sub(vespene),
+			supply: (self.supply - supply_left).max(0.0),
+			time: 0.0,
+		}
+	}
+	/// Total value of resources, where vespene is multiplied by `gas_weight`.
+	///
+	/// Vespene is harder to gather, so weight greater than `1` may be used to value it more.
+	pub fn resource_value(&self, gas_weight: f32) -> f32 {
+		self.minerals as f32 + self.vespene as f32 * gas_weight
+	}
+}
+impl PartialOrd for Cost {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		let total = |cost: &Cost| cost.minerals as u64 + cost.vespene as u64;
+		match total(self).cmp(&total(other)) {
+			Ordering::Equal => (self.minerals, self.vespene, self.supply, self.time).partial_cmp(&(
+				other.minerals,
+				other.vespene,
+				other.supply,
+				other.time,
+			)),
+			ordering => Some(ordering),
+		}
+	}
+}
+
+/// Total cost of producing all given unit types.
+///
+/// Costs are taken as reported by API, so morphs include cost of unit they're morphed from.
+/// Unknown ids are skipped.
+pub fn build_order_cost(data: &GameData, items: &[UnitTypeId]) -> Cost {
+	items
+		.iter()
+		.filter_map(|id| data.units.get(id))
+		.fold(Cost::default(), |mut total, unit| {
+			total.minerals += unit.mineral_cost;
+			total.vespene += unit.vespene_cost;
+			total.supply += unit.food_required;
+			total.time += unit.build_time;
+			total
+		})
+}
+/// Time to produce all given unit types one after another.
+///
+/// Unknown ids are skipped, their number is returned along with the time.
+pub fn build_order_time_sequential(data: &GameData, items: &[UnitTypeId]) -> (GameTime, usize) {
+	let loops = items
+		.iter()
+		.filter_map(|id| data.units.get(id))
+		.map(|unit| unit.build_time)
+		.sum::<f32>();
+	let time = GameTime::from_loops(loops.round() as u32);
+	(time, count_unknown(data, items))
+}
+/// Time to produce all given unit types simultaneously, i.e. build time of the longest one.
+///
+/// Unknown ids are skipped, their number is returned along with the time.
+pub fn build_order_time_parallel(data: &GameData, items: &[UnitTypeId]) -> (GameTime, usize) {
+	let loops = items
+		.iter()
+		.filter_map(|id| data.units.get(id))
+		.map(|unit| unit.build_time)
+		.fold(0.0, f32::max);
+	let time = GameTime::from_loops(loops.round() as u32);
+	(time, count_unknown(data, items))
+}
+fn count_unknown(data: &GameData, items: &[UnitTypeId]) -> usize {
+	items.iter().filter(|id| !data.units.contains_key(id)).count()
+}
 
 /// Possible target of ability, needed when giving commands to units.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -121,6 +539,26 @@ impl FromProto<ability_data::Target> for AbilityTarget {
 		}
 	}
 }
+impl AbilityTarget {
+	/// Checks if ability can be targeted on a point.
+	#[inline]
+	pub fn accepts_point(self) -> bool {
+		matches!(
+			self,
+			AbilityTarget::Point | AbilityTarget::PointOrUnit | AbilityTarget::PointOrNone
+		)
+	}
+	/// Checks if ability can be targeted on a unit.
+	#[inline]
+	pub fn accepts_unit(self) -> bool {
+		matches!(self, AbilityTarget::Unit | AbilityTarget::PointOrUnit)
+	}
+	/// Checks if ability can be used without target.
+	#[inline]
+	pub fn accepts_none(self) -> bool {
+		matches!(self, AbilityTarget::None | AbilityTarget::PointOrNone)
+	}
+}
 
 /// Differents attributes of units.
 #[variant_checkers]
@@ -177,6 +615,17 @@ impl FromProto<weapon::TargetType> for TargetType {
 		}
 	}
 }
+impl TargetType {
+	/// Checks if weapon with this target type can hit flying (if `flying` is `true`) or ground target.
+	#[inline]
+	pub fn covers(self, flying: bool) -> bool {
+		match self {
+			TargetType::Any => true,
+			TargetType::Ground => !flying,
+			TargetType::Air => flying,
+		}
+	}
+}
 
 /// Weapon's characteristic.
 #[derive(Clone)]
@@ -195,6 +644,77 @@ pub struct Weapon {
 	/// Cooldown (in seconds * game speed).
 	pub speed: f32,
 }
+impl Weapon {
+	/// Damage of single use of weapon (all its attacks) without bonuses and armor.
+	#[inline]
+	pub fn total_damage(&self) -> u32 {
+		self.damage * self.attacks
+	}
+	/// Damage of single use of weapon (all its attacks) against target with given attributes,
+	/// including bonus damage.
+	#[inline]
+	pub fn total_damage_vs(&self, attributes: &[Attribute]) -> u32 {
+		self.damage_vs(attributes) * self.attacks
+	}
+	/// Checks if weapon hits multiple times per use (e.g. Zealot or Thor's air attack).
+	#[inline]
+	pub fn is_multi_hit(&self) -> bool {
+		self.attacks > 1
+	}
+	/// Damage per second (in game speed) without bonuses.
+	pub fn dps(&self) -> f32 {
+		if self.speed == 0.0 {
+			return 0.0;
+		}
+		self.total_damage() as f32 / self.speed
+	}
+	/// Damage per second (in game speed) against target with given attributes,
+	/// including bonus damage.
+	pub fn dps_vs(&self, attributes: &[Attribute]) -> f32 {
+		if self.speed == 0.0 {
+			return 0.0;
+		}
+		self.total_damage_vs(attributes) as f32 / self.speed
+	}
+	/// Damage of single attack with given level of attack upgrades.
+	///
+	/// Assumes standard bonus of `+1` damage per level, which applies to each attack of weapon.
+	/// Use [`damage_with_upgrades_for`](Self::damage_with_upgrades_for) to take
+	/// unit specific bonuses into account.
+	pub fn damage_with_upgrades(&self, level: u32) -> u32 {
+		self.damage + level
+	}
+	/// Damage of single attack with given level of attack upgrades of given unit type.
+	///
+	/// Bonus per level is taken from the same table used by [`Unit`](crate::unit::Unit)'s
+	/// damage calculations, falling back to `+1` for units not listed there.
+	pub fn damage_with_upgrades_for(&self, unit: UnitTypeId, level: u32) -> u32 {
+		let bonus = DAMAGE_BONUS_PER_UPGRADE
+			.get(&unit)
+			.and_then(|bonus| bonus.get(&self.target))
+			.and_then(|bonus| bonus.0)
+			.unwrap_or(1);
+		self.damage + level * bonus
+	}
+	/// Damage of single attack against target with given attributes, including bonus damage.
+	pub fn damage_vs(&self, attributes: &[Attribute]) -> u32 {
+		self.damage
+			+ self
+				.damage_bonus
+				.iter()
+				.filter(|(attribute, _)| attributes.contains(attribute))
+				.map(|(_, bonus)| bonus)
+				.sum::<u32>()
+	}
+}
+/// Returns maximum distance between centers of attacker and target at which attack is possible.
+///
+/// Engine measures weapon range from edge to edge of units,
+/// so radii of both units are added to the range of weapon.
+#[inline]
+pub fn effective_range(weapon_range: f32, attacker_radius: f32, target_radius: f32) -> f32 {
+	weapon_range + attacker_radius + target_radius
+}
 impl FromProto<&ProtoWeapon> for Weapon {
 	#[inline]
 	fn from_proto(weapon: &ProtoWeapon) -> Self {
@@ -236,10 +756,40 @@ pub struct AbilityData {
 	pub is_building: bool,
 	/// Half of the building size.
 	pub footprint_radius: Option<f32>,
+	/// Building is placed instantly without usual placement rules (e.g. creep tumors),
+	/// see [`skip_placement_check`](Self::skip_placement_check).
 	pub is_instant_placement: bool,
 	/// Maximum range to target of the ability.
 	pub cast_range: Option<f32>,
 }
+impl AbilityData {
+	/// Checks if ability must be given a target (point or unit).
+	#[inline]
+	pub fn needs_target(&self) -> bool {
+		!self.can_target_none()
+	}
+	/// Checks if ability can be targeted on a point.
+	#[inline]
+	pub fn needs_point(&self) -> bool {
+		self.target.accepts_point()
+	}
+	/// Checks if ability can be targeted on a unit.
+	#[inline]
+	pub fn needs_unit(&self) -> bool {
+		self.target.accepts_unit()
+	}
+	/// Checks if ability can be used without target.
+	#[inline]
+	pub fn can_target_none(&self) -> bool {
+		self.target.accepts_none()
+	}
+	/// Checks if building created by this ability bypasses normal placement rules,
+	/// so placement checks against pathing and placement grids should be skipped.
+	#[inline]
+	pub fn skip_placement_check(&self) -> bool {
+		self.is_instant_placement
+	}
+}
 impl TryFromProto<ProtoAbilityData> for AbilityData {
 	#[inline]
 	fn try_from_proto(mut a: ProtoAbilityData) -> Option<Self> {
@@ -281,6 +831,7 @@ pub struct UnitTypeData {
 	pub ability: Option<AbilityId>,
 	/// Race of unit.
 	pub race: Race,
+	/// Time in game loops, see [`GameTime`](crate::time::GameTime) for conversions.
 	pub build_time: f32,
 	/// Unit contains vespene (i.e. is vespene geyser).
 	pub has_vespene: bool,
@@ -305,6 +856,88 @@ impl UnitTypeData {
 			time: self.build_time,
 		}
 	}
+	/// Checks if unit type has `Structure` attribute.
+	#[inline]
+	pub fn is_structure(&self) -> bool {
+		self.attributes.contains(&Attribute::Structure)
+	}
+	/// Checks if unit type is a lifted terran building.
+	#[inline]
+	pub fn is_flying_structure(&self) -> bool {
+		self.is_structure()
+			&& self.movement_speed > 0.0
+			&& !matches!(
+				self.id,
+				UnitTypeId::SpineCrawlerUprooted | UnitTypeId::SporeCrawlerUprooted
+			)
+	}
+	/// Checks if unit type is a terran addon (techlab or reactor).
+	#[inline]
+	pub fn is_addon(&self) -> bool {
+		self.is_structure() && self.id.is_addon()
+	}
+	/// Damage dealt to given target by single use of the best weapon able to hit it,
+	/// or `None` if unit can't attack target.
+	///
+	/// Includes bonus damage vs target's attributes and number of attacks.
+	/// Every attack is reduced by target's armor, but deals at least 0.5 damage.
+	pub fn damage_against(&self, target: &UnitTypeData, target_armor_upgrades: i32) -> Option<f32> {
+		let (hits_ground, hits_air) = match target.id {
+			// Colossus can be hit by both ground and air weapons
+			UnitTypeId::Colossus => (true, true),
+			id if id.is_flying() => (false, true),
+			_ => (true, false),
+		};
+		let armor = (target.armor + target_armor_upgrades) as f32;
+
+		self.weapons
+			.iter()
+			.filter(|w| (hits_ground && w.target.covers(false)) || (hits_air && w.target.covers(true)))
+			.map(|w| (w.damage_vs(&target.attributes) as f32 - armor).max(0.5) * w.attacks as f32)
+			.max_by(|a, b| a.total_cmp(b))
+	}
+	/// Checks if any weapon of unit type hits multiple times per use, see [`Weapon::is_multi_hit`].
+	///
+	/// Note that splash damage isn't reported in game data, so it's not taken into account.
+	pub fn is_multi_hit(&self) -> bool {
+		self.weapons.iter().any(|w| w.is_multi_hit())
+	}
+	/// Checks if unit type has given attribute.
+	pub fn has_attribute(&self, attribute: Attribute) -> bool {
+		self.attributes.contains(&attribute)
+	}
+	/// Checks if unit type has any of given attributes.
+	pub fn has_any(&self, attributes: &[Attribute]) -> bool {
+		attributes.iter().any(|a| self.attributes.contains(a))
+	}
+	/// Checks if unit has weapon able to hit ground or air target.
+	pub fn can_attack(&self, target_is_flying: bool) -> bool {
+		if target_is_flying {
+			self.can_attack_air()
+		} else {
+			self.can_attack_ground()
+		}
+	}
+	/// Checks if unit has weapon able to hit ground targets.
+	pub fn can_attack_ground(&self) -> bool {
+		self.weapons.iter().any(|w| w.target.covers(false))
+	}
+	/// Checks if unit has weapon able to hit air targets.
+	pub fn can_attack_air(&self) -> bool {
+		self.weapons.iter().any(|w| w.target.covers(true))
+	}
+	/// Largest range across all weapons of unit, `0` if unit has no weapons.
+	pub fn max_range(&self) -> f32 {
+		self.weapons.iter().map(|w| w.range).fold(0.0, f32::max)
+	}
+	/// Smallest range across all weapons of unit, `0` if unit has no weapons.
+	pub fn min_range(&self) -> f32 {
+		self.weapons
+			.iter()
+			.map(|w| w.range)
+			.reduce(f32::min)
+			.unwrap_or(0.0)
+	}
 }
 impl TryFromProto<ProtoUnitTypeData> for UnitTypeData {
 	#[inline]
@@ -354,6 +987,7 @@ pub struct UpgradeData {
 	pub name: String,
 	pub mineral_cost: u32,
 	pub vespene_cost: u32,
+	/// Time in game loops, see [`GameTime`](crate::time::GameTime) for conversions.
 	pub research_time: f32,
 }
 impl UpgradeData {
@@ -410,26 +1044,132 @@ pub struct EffectData {
 	/// `true` if effect affects allied units.
 	pub friendly_fire: bool,
 }
+/// Returns which units are affected by given effect and if it affects own units.
+///
+/// Disruptor's Purification Nova isn't reported by API as effect,
+/// use [`classify_unit_effect`] for it.
+pub fn classify_effect(id: EffectId) -> (TargetType, bool) {
+	match id {
+		EffectId::Null | EffectId::ScannerSweep => (TargetType::Any, false),
+		EffectId::PsiStormPersistent | EffectId::NukePersistent | EffectId::RavagerCorrosiveBileCP => {
+			(TargetType::Any, true)
+		}
+		// Liberator zone only hits ground units of opponent
+		EffectId::LiberatorTargetMorphDelayPersistent | EffectId::LiberatorTargetMorphPersistent => {
+			(TargetType::Ground, false)
+		}
+		// Blinding cloud reduces range of all ground units under it, including own ones
+		EffectId::BlindingCloudCP => (TargetType::Ground, true),
+		EffectId::GuardianShieldPersistent
+		| EffectId::TemporalFieldGrowingBubbleCreatePersistent
+		| EffectId::TemporalFieldAfterBubbleCreatePersistent
+		| EffectId::ThermalLancesForward
+		| EffectId::LurkerMP => (TargetType::Ground, false),
+	}
+}
+/// Returns which units are affected by effect represented by unit of given type
+/// and if it affects own units, or `None` if unit isn't an effect.
+///
+/// Covers effects which API reports as units instead of [`EffectId`]s,
+/// like Disruptor's Purification Nova ([`DisruptorPhased`](UnitTypeId::DisruptorPhased)).
+pub fn classify_unit_effect(unit: UnitTypeId) -> Option<(TargetType, bool)> {
+	match unit {
+		UnitTypeId::DisruptorPhased => Some((TargetType::Ground, true)),
+		_ => None,
+	}
+}
+
 impl TryFromProto<ProtoEffectData> for EffectData {
 	#[inline]
 	fn try_from_proto(e: ProtoEffectData) -> Option<Self> {
-		EffectId::from_u32(e.effect_id()).map(|id| Self {
+		let id = EffectId::from_u32(e.effect_id())?;
+		let (target, friendly_fire) = classify_effect(id);
+		Some(Self {
 			id,
 			name: e.name().to_string(),
 			friendly_name: e.friendly_name().to_string(),
 			radius: e.radius(),
-			target: match id {
-				EffectId::Null
-				| EffectId::PsiStormPersistent
-				| EffectId::ScannerSweep
-				| EffectId::NukePersistent
-				| EffectId::RavagerCorrosiveBileCP => TargetType::Any,
-				_ => TargetType::Ground,
-			},
-			friendly_fire: matches!(
-				id,
-				EffectId::PsiStormPersistent | EffectId::NukePersistent | EffectId::RavagerCorrosiveBileCP
-			),
+			target,
+			friendly_fire,
 		})
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn effects_are_classified() {
+		use EffectId::*;
+		use TargetType::*;
+
+		let table = [
+			(PsiStormPersistent, Any, true),
+			(NukePersistent, Any, true),
+			(RavagerCorrosiveBileCP, Any, true),
+			(ScannerSweep, Any, false),
+			(LiberatorTargetMorphDelayPersistent, Ground, false),
+			(LiberatorTargetMorphPersistent, Ground, false),
+			(BlindingCloudCP, Ground, true),
+			(LurkerMP, Ground, false),
+			(ThermalLancesForward, Ground, false),
+		];
+		for (id, target, friendly_fire) in table {
+			assert_eq!(classify_effect(id), (target, friendly_fire), "{:?}", id);
+		}
+	}
+
+	#[test]
+	fn target_type_covers() {
+		assert!(TargetType::Any.covers(false));
+		assert!(TargetType::Any.covers(true));
+		assert!(TargetType::Ground.covers(false));
+		assert!(!TargetType::Ground.covers(true));
+		assert!(!TargetType::Air.covers(false));
+		assert!(TargetType::Air.covers(true));
+	}
+
+	#[test]
+	fn stalker_range_against_zergling() {
+		// Stalker: range 6, radius 0.625; Zergling: radius 0.375
+		assert_eq!(effective_range(6.0, 0.625, 0.375), 7.0);
+	}
+
+	#[test]
+	fn unit_effects_are_classified() {
+		assert_eq!(
+			classify_unit_effect(UnitTypeId::DisruptorPhased),
+			Some((TargetType::Ground, true))
+		);
+		assert_eq!(classify_unit_effect(UnitTypeId::Disruptor), None);
+	}
+
+	#[test]
+	fn build_order_time_skips_unknown() {
+		let mut data = GameData::default();
+		for (id, build_time) in [(UnitTypeId::Marine, 400.0), (UnitTypeId::SCV, 272.0)] {
+			let mut proto = ProtoUnitTypeData::new();
+			proto.set_unit_id(id as u32);
+			proto.set_build_time(build_time);
+			data.units
+				.insert(id, UnitTypeData::try_from_proto(proto).unwrap());
+		}
+		let items = [
+			UnitTypeId::Marine,
+			UnitTypeId::SCV,
+			UnitTypeId::Marauder,
+			UnitTypeId::Marine,
+		];
+
+		assert_eq!(
+			build_order_time_sequential(&data, &items),
+			(GameTime::from_loops(1072), 1)
+		);
+		assert_eq!(
+			build_order_time_parallel(&data, &items),
+			(GameTime::from_loops(400), 1)
+		);
+		assert_eq!(build_order_time_parallel(&data, &[]), (GameTime::default(), 0));
+	}
+}
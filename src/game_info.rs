@@ -3,16 +3,47 @@
 use crate::{
 	FromProto,
 	bot::Rs,
-	geometry::{Point2, Rect, Size},
-	pixel_map::{ByteMap, PixelMap},
+	distance::{Center, Distance, DistanceIterator},
+	game_data::AbilityData,
+	geometry::{Point2, Rect, Size, TilePos},
+	pixel_map::{ByteMap, Connectivity, Pixel, PixelMap, PixelMapExt},
 	player::{AIBuild, Difficulty, PlayerType, Race},
+	ramp::Ramp,
+	utils::{dbscan, range_query},
 };
-use rustc_hash::FxHashMap;
+use ndarray::Array2;
+use once_cell::sync::OnceCell;
+use rand::Rng;
+use rustc_hash::{FxHashMap, FxHashSet};
 use sc2_proto::sc2api::ResponseGameInfo;
-use std::{ops::Deref, path::Path};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+	cmp::Reverse,
+	collections::{BinaryHeap, VecDeque},
+	ops::Deref,
+	path::Path,
+};
+
+type Pos = (usize, usize);
+
+const NEIGHBORS8: [(isize, isize); 8] = [
+	(1, 0),
+	(-1, 0),
+	(0, 1),
+	(0, -1),
+	(1, 1),
+	(-1, -1),
+	(1, -1),
+	(-1, 1),
+];
 
 /// Structure where all map information stored.
+///
+/// With `serde` feature it can be serialized, e.g. to capture it from real game
+/// and use later for offline map analysis.
 #[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GameInfo {
 	/// Map name bot playing on, which depends on sc2 localization language.
 	pub map_name: String,
@@ -39,6 +70,632 @@ pub struct GameInfo {
 	pub start_locations: Vec<Point2>,
 	/// Center of the map.
 	pub map_center: Point2,
+	#[cfg_attr(feature = "serde", serde(skip))]
+	buildable_mask: OnceCell<PixelMap>,
+}
+impl GameInfo {
+	/// Returns all players except observers and player with given id.
+	pub fn opponents(&self, my_id: u32) -> impl Iterator<Item = &PlayerInfo> {
+		self.players
+			.values()
+			.filter(move |p| p.id != my_id && p.player_type != PlayerType::Observer)
+	}
+	/// Returns opponent with the lowest id, see [`opponents`](Self::opponents).
+	///
+	/// Useful in 1v1 games, where there's only one opponent.
+	pub fn first_opponent(&self, my_id: u32) -> Option<&PlayerInfo> {
+		self.opponents(my_id).min_by_key(|p| p.id)
+	}
+	/// Returns townhall locations for all clusters of given resources (minerals and geysers).
+	///
+	/// Resources are grouped by distance and for each group the closest position
+	/// where 5x5 townhall can be placed without overlapping resources is chosen.
+	/// Geysers are recognized by their center, which is always at `x.5, y.5`.
+	///
+	/// Tiles under existing townhalls aren't placeable, so their positions should be passed in `townhalls`
+	/// (e.g. own start location) to get locations of occupied expansions too.
+	/// [`start_locations`](Self::start_locations) are always treated as occupied.
+	pub fn expansion_locations(&self, resources: &[Point2], townhalls: &[Point2]) -> Vec<Point2> {
+		group_resources(resources)
+			.into_iter()
+			.filter_map(|group| {
+				let group = group.into_iter().map(|i| resources[i]).collect::<Vec<_>>();
+				self.townhall_location(&group, townhalls)
+			})
+			.collect()
+	}
+	/// Returns position where 5x5 townhall can be placed closest to given group of resources,
+	/// but far enough to not overlap them, or `None` if there's no such position.
+	///
+	/// Positions of given `townhalls` and start locations are accepted even though they aren't placeable.
+	pub(crate) fn townhall_location(&self, resources: &[Point2], townhalls: &[Point2]) -> Option<Point2> {
+		const OFFSET: isize = 7;
+
+		if resources.is_empty() {
+			return None;
+		}
+		let sum = resources.iter().copied().sum::<Point2>();
+		let center = (sum / resources.len() as f32).floor() + 0.5;
+
+		iproduct!(-OFFSET..=OFFSET, -OFFSET..=OFFSET)
+			.filter(|(x, y)| {
+				let d = x * x + y * y;
+				16 < d && d <= 64
+			})
+			.filter_map(|(x, y)| {
+				let pos = center.offset(x as f32, y as f32);
+				let occupied = townhalls.contains(&pos) || self.start_locations.contains(&pos);
+				if !occupied && !self.is_townhall_placeable(pos) {
+					return None;
+				}
+
+				let mut distance_sum = 0_f32;
+				let far_enough = |r: &Point2| {
+					let dist = pos.distance_squared(*r);
+					distance_sum += dist;
+					let is_geyser = r.x.fract() == 0.5 && r.y.fract() == 0.5;
+					dist >= if is_geyser { 49.0 } else { 36.0 }
+				};
+				resources.iter().all(far_enough).then_some((pos, distance_sum))
+			})
+			.min_by(|(_, d1), (_, d2)| d1.total_cmp(d2))
+			.map(|(pos, _)| pos)
+	}
+	/// Finds all ramps on the map, i.e. groups of pathable but not placeable tiles,
+	/// which have neighbors on different height.
+	///
+	/// Own start location isn't known here, so methods of returned ramps which depend on it
+	/// (like [`protoss_wall_buildings`](Ramp::protoss_wall_buildings)) may return wrong results.
+	/// Use [`ramps`](crate::bot::Bot::ramps) field of bot for that.
+	pub fn find_ramps(&self) -> Vec<Ramp> {
+		self.find_ramps_and_vision_blockers(Point2::default()).0
+	}
+	/// Finds narrow pathable passages on the map, which are at most `max_width` tiles wide.
+	///
+	/// Width of passage is measured on each pathable tile along 4 directions (horizontal, vertical and diagonals),
+	/// tile belongs to passage if it's narrow in one direction and at least twice as long in the perpendicular one.
+	/// Connected tiles of passages are grouped into chokepoints, and only those which open up
+	/// on both sides are returned (i.e. narrow dead ends are skipped).
+	///
+	/// Result is approximate, values of `max_width` around `3.0`-`6.0` usually work well.
+	pub fn find_chokepoints(&self, max_width: f32) -> Vec<Chokepoint> {
+		const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+		// Index of perpendicular direction
+		const PERPENDICULAR: [usize; 4] = [1, 0, 3, 2];
+
+		let limit = (max_width * 3.0).ceil() as usize;
+		let step = |x: usize, d: isize| x.checked_add_signed(d);
+		// Number of pathable tiles in line through given position, limited to `2 * limit + 1`
+		let width = |(x, y): Pos, i: usize| {
+			let (dx, dy) = DIRECTIONS[i];
+			let mut count = 1;
+			for sign in [1, -1] {
+				let (mut cx, mut cy) = (x, y);
+				for _ in 0..limit {
+					match (step(cx, dx * sign), step(cy, dy * sign)) {
+						(Some(nx), Some(ny)) if self.is_pathable_tile((nx, ny)) => (cx, cy) = (nx, ny),
+						_ => break,
+					}
+					count += 1;
+				}
+			}
+			let len = if i < 2 { 1.0 } else { std::f32::consts::SQRT_2 };
+			count as f32 * len
+		};
+
+		let (w, h) = self.pathing_grid.dim();
+		let mut candidates = FxHashMap::default();
+		for pos in iproduct!(0..w, 0..h) {
+			if !self.is_pathable_tile(pos) {
+				continue;
+			}
+			let widths = [0, 1, 2, 3].map(|i| width(pos, i));
+			let (i, min) = widths
+				.iter()
+				.copied()
+				.enumerate()
+				.min_by(|(_, a), (_, b)| a.total_cmp(b))
+				.unwrap();
+			if min <= max_width && widths[PERPENDICULAR[i]] >= min * 2.0 {
+				candidates.insert(pos, (i, min));
+			}
+		}
+
+		let direction = |i: usize| {
+			let (dx, dy) = DIRECTIONS[i];
+			Point2::new(dx as f32, dy as f32).normalize()
+		};
+
+		let mut chokepoints = Vec::new();
+		while let Some(&start) = candidates.keys().next() {
+			// Collecting connected candidates
+			let mut group = vec![];
+			let mut stack = vec![start];
+			while let Some(pos) = stack.pop() {
+				let Some(data) = candidates.remove(&pos) else {
+					continue;
+				};
+				group.push((pos, data));
+				let (x, y) = pos;
+				for (dx, dy) in NEIGHBORS8 {
+					if let (Some(nx), Some(ny)) = (step(x, dx), step(y, dy))
+						&& candidates.contains_key(&(nx, ny))
+					{
+						stack.push((nx, ny));
+					}
+				}
+			}
+
+			let mut counts = [0; 4];
+			for (_, (i, _)) in &group {
+				counts[*i] += 1;
+			}
+			let narrow = (0..4).max_by_key(|i| counts[*i]).unwrap();
+			let open = direction(PERPENDICULAR[narrow]);
+			let chokepoint_width = group
+				.iter()
+				.map(|(_, (_, w))| *w)
+				.min_by(|a, b| a.total_cmp(b))
+				.unwrap();
+			let tiles = group
+				.iter()
+				.map(|(pos, _)| Point2::from(*pos))
+				.collect::<Vec<_>>();
+			let center = tiles.iter().center().unwrap();
+
+			// Checking that passage opens up on both sides
+			let extent = tiles
+				.iter()
+				.map(|p| (*p - center).dot(open).abs())
+				.fold(0.0, f32::max);
+			let opens_up = [open, -open].into_iter().all(|dir| {
+				let p = center + dir * (extent + 2.0);
+				p.x >= 0.0
+					&& p.y >= 0.0 && self.is_pathable_tile(p.to_tile())
+					&& width(p.to_tile(), narrow) > chokepoint_width * 1.5
+			});
+			if !opens_up {
+				continue;
+			}
+
+			chokepoints.push(Chokepoint {
+				center,
+				width: chokepoint_width,
+				directions: [open, -open],
+				tiles,
+			});
+		}
+		chokepoints
+	}
+	/// Splits pathable area of the map into regions separated by chokepoints.
+	///
+	/// Chokepoints are found with [`find_chokepoints`](Self::find_chokepoints) using given `max_choke_width`,
+	/// their tiles aren't included in any region. Regions are adjacent if they're connected by a chokepoint.
+	/// Index of region in returned `Vec` is its [`id`](Region::id).
+	pub fn regions(&self, max_choke_width: f32) -> Vec<Region> {
+		let chokepoints = self.find_chokepoints(max_choke_width);
+		let mut grid = self.pathing_grid.clone();
+		for tile in chokepoints.iter().flat_map(|c| &c.tiles) {
+			grid[*tile] = Pixel::Set;
+		}
+
+		let components = grid.connected_components(Connectivity::Eight);
+		let region_of = components
+			.iter()
+			.enumerate()
+			.flat_map(|(i, tiles)| tiles.iter().map(move |p| (TilePos::from(*p), i)))
+			.collect::<FxHashMap<_, _>>();
+
+		let mut adjacent = vec![FxHashSet::default(); components.len()];
+		for c in &chokepoints {
+			let touching = c
+				.tiles
+				.iter()
+				.flat_map(|t| TilePos::from(*t).neighbors())
+				.filter_map(|t| region_of.get(&t).copied())
+				.collect::<FxHashSet<_>>();
+			for &a in &touching {
+				adjacent[a].extend(touching.iter().filter(|b| **b != a));
+			}
+		}
+
+		components
+			.into_iter()
+			.zip(adjacent)
+			.enumerate()
+			.map(|(id, (tiles, adjacent))| {
+				let tiles = tiles.into_iter().collect::<Vec<_>>();
+				let mean = tiles.iter().center().unwrap_or_default();
+				// Mean of non-convex region may be outside of it
+				let center = tiles.iter().copied().closest(mean).unwrap_or(mean);
+				let mut adjacent = adjacent.into_iter().collect::<Vec<_>>();
+				adjacent.sort_unstable();
+				Region {
+					id,
+					tiles,
+					center,
+					adjacent,
+				}
+			})
+			.collect()
+	}
+	pub(crate) fn find_ramps_and_vision_blockers(&self, start_location: Point2) -> (Vec<Ramp>, Vec<Point2>) {
+		let is_pathable = |pos| self.pathing_grid.get(pos).is_some_and(|p| p.is_empty());
+		let is_placeable = |pos| self.placement_grid.get(pos).is_some_and(|p| p.is_empty());
+		let get_height = |pos| self.terrain_height.get(pos).copied().unwrap_or(0);
+
+		let mut ramp_points = FxHashSet::default();
+		let mut vision_blockers = Vec::new();
+
+		let area = self.playable_area;
+		for pos in iproduct!(area.x0..area.x1, area.y0..area.y1) {
+			if !is_pathable(pos) || is_placeable(pos) {
+				continue;
+			}
+
+			let h = get_height(pos);
+			let (x, y) = pos;
+
+			let neighbors = [
+				(x + 1, y),
+				(x - 1, y),
+				(x, y + 1),
+				(x, y - 1),
+				(x + 1, y + 1),
+				(x - 1, y - 1),
+				(x + 1, y - 1),
+				(x - 1, y + 1),
+			];
+
+			if neighbors.iter().all(|p| get_height(*p) == h) {
+				vision_blockers.push(Point2::new(x as f32, y as f32));
+			} else {
+				ramp_points.insert(pos);
+			}
+		}
+
+		let ramps = dbscan(
+			&ramp_points,
+			|&(x, y)| {
+				[
+					(x + 1, y),
+					(x - 1, y),
+					(x, y + 1),
+					(x, y - 1),
+					(x + 1, y + 1),
+					(x - 1, y - 1),
+					(x + 1, y - 1),
+					(x - 1, y + 1),
+				]
+				.iter()
+				.filter(|n| ramp_points.contains(n))
+				.copied()
+				.collect()
+			},
+			1,
+		)
+		.0
+		.into_iter()
+		.filter(|ps| ps.len() >= 8)
+		.map(|ps| Ramp::new(ps, &self.terrain_height, start_location))
+		.collect();
+
+		(ramps, vision_blockers)
+	}
+	/// Finds shortest path for ground units between given points using A* over `pathing_grid`.
+	///
+	/// Returns centers of tiles in the path, including start and goal,
+	/// or `None` if goal is unreachable.
+	/// If start or goal is not pathable, the closest pathable tile is used instead.
+	pub fn path(&self, start: Point2, goal: Point2) -> Option<Vec<Point2>> {
+		self.find_path(start, goal, None)
+	}
+	/// Same as [`path`](Self::path), but tries to go around tiles which are set in `avoid` map
+	/// (e.g. tiles in range of enemy units).
+	///
+	/// Avoided tiles are still passable, so path is found even if there's no way around them.
+	pub fn path_avoiding(&self, start: Point2, goal: Point2, avoid: &PixelMap) -> Option<Vec<Point2>> {
+		self.find_path(start, goal, Some(avoid))
+	}
+	fn find_path(&self, start: Point2, goal: Point2, avoid: Option<&PixelMap>) -> Option<Vec<Point2>> {
+		const STRAIGHT: u32 = 10;
+		const DIAGONAL: u32 = 14;
+		const AVOID_PENALTY: u32 = 100;
+
+		let start = self.closest_pathable_tile(start)?;
+		let goal = self.closest_pathable_tile(goal)?;
+
+		let heuristic = |(x, y): Pos| {
+			let dx = x.abs_diff(goal.0) as u32;
+			let dy = y.abs_diff(goal.1) as u32;
+			STRAIGHT * dx.max(dy) + (DIAGONAL - STRAIGHT) * dx.min(dy)
+		};
+
+		let mut open = BinaryHeap::new();
+		let mut costs = FxHashMap::<Pos, u32>::default();
+		let mut came_from = FxHashMap::<Pos, Pos>::default();
+
+		costs.insert(start, 0);
+		open.push(Reverse((heuristic(start), 0, start)));
+
+		while let Some(Reverse((_, cost, current))) = open.pop() {
+			if current == goal {
+				let mut path = vec![Point2::from(current)];
+				let mut current = current;
+				while let Some(&prev) = came_from.get(&current) {
+					path.push(Point2::from(prev));
+					current = prev;
+				}
+				path.reverse();
+				return Some(path);
+			}
+			// Skip outdated entries
+			if costs.get(&current).is_some_and(|&c| c < cost) {
+				continue;
+			}
+
+			let (x, y) = current;
+			for (dx, dy) in NEIGHBORS8 {
+				let (Some(nx), Some(ny)) = (x.checked_add_signed(dx), y.checked_add_signed(dy)) else {
+					continue;
+				};
+				let next = (nx, ny);
+				if !self.is_pathable_tile(next) {
+					continue;
+				}
+
+				let step = if dx != 0 && dy != 0 {
+					// Don't cut corners
+					if !self.is_pathable_tile((nx, y)) || !self.is_pathable_tile((x, ny)) {
+						continue;
+					}
+					DIAGONAL
+				} else {
+					STRAIGHT
+				};
+				let penalty = if avoid
+					.and_then(|avoid| avoid.get(next))
+					.is_some_and(|p| p.is_set())
+				{
+					AVOID_PENALTY
+				} else {
+					0
+				};
+
+				let new_cost = cost + step + penalty;
+				if costs.get(&next).is_none_or(|&c| new_cost < c) {
+					costs.insert(next, new_cost);
+					came_from.insert(next, current);
+					open.push(Reverse((new_cost + heuristic(next), new_cost, next)));
+				}
+			}
+		}
+
+		None
+	}
+	/// Returns map of tiles which are both placeable and pathable (`Empty`),
+	/// combining `placement_grid` and `pathing_grid`, so placement checks need only one grid.
+	///
+	/// Mask is computed on first call and cached, so changes of these grids made afterwards aren't reflected.
+	/// It contains only static map data: creep, current units and structures
+	/// still have to be checked with live data (e.g. with [`can_place`](crate::bot::Bot::can_place)).
+	pub fn buildable_mask(&self) -> &PixelMap {
+		self.buildable_mask
+			.get_or_init(|| self.placement_grid.union(&self.pathing_grid))
+	}
+	/// Computes ground distance in tiles from every tile of the map to the closest of given seeds,
+	/// using multi-source BFS over `pathing_grid` with the same moves as [`path`](Self::path).
+	///
+	/// Diagonal steps count as one tile. Unreachable and not pathable tiles have `u32::MAX` distance.
+	/// Seeds which aren't pathable (e.g. structures) are moved to the closest pathable tile.
+	///
+	/// Result is a flat vector where tile `(x, y)` is stored at index `x * height + y`,
+	/// `height` being the height of `pathing_grid`. Use [`distance_at`](Self::distance_at) to read it.
+	pub fn distance_field(&self, seeds: &[Point2]) -> Vec<u32> {
+		let mut field = Array2::from_elem(self.pathing_grid.dim(), u32::MAX);
+		let mut queue = VecDeque::new();
+		for seed in seeds.iter().filter_map(|seed| self.closest_pathable_tile(*seed)) {
+			if field[seed] != 0 {
+				field[seed] = 0;
+				queue.push_back(seed);
+			}
+		}
+
+		while let Some(current) = queue.pop_front() {
+			let (x, y) = current;
+			let distance = field[current] + 1;
+			for (dx, dy) in NEIGHBORS8 {
+				let (Some(nx), Some(ny)) = (x.checked_add_signed(dx), y.checked_add_signed(dy)) else {
+					continue;
+				};
+				let next = (nx, ny);
+				if !self.is_pathable_tile(next) || field[next] <= distance {
+					continue;
+				}
+				// Don't cut corners
+				let diagonal = dx != 0 && dy != 0;
+				if diagonal && (!self.is_pathable_tile((nx, y)) || !self.is_pathable_tile((x, ny))) {
+					continue;
+				}
+				field[next] = distance;
+				queue.push_back(next);
+			}
+		}
+
+		field.into_raw_vec_and_offset().0
+	}
+	/// Returns distance at given point of field computed by [`distance_field`](Self::distance_field),
+	/// `u32::MAX` if point is outside of the map.
+	pub fn distance_at(&self, field: &[u32], p: Point2) -> u32 {
+		let (width, height) = self.pathing_grid.dim();
+		if p.x < 0.0 || p.y < 0.0 {
+			return u32::MAX;
+		}
+		let (x, y) = p.to_tile();
+		if x >= width || y >= height {
+			return u32::MAX;
+		}
+		field.get(x * height + y).copied().unwrap_or(u32::MAX)
+	}
+	fn is_pathable_tile(&self, pos: Pos) -> bool {
+		self.pathing_grid.get(pos).is_some_and(|p| p.is_empty())
+	}
+	/// Returns given point if it's pathable, otherwise center of the closest pathable tile.
+	/// If there're no pathable tiles on the map, returns given point unchanged.
+	pub fn closest_pathable(&self, p: Point2) -> Point2 {
+		match self.closest_pathable_tile(p) {
+			Some(pos) if pos == <(usize, usize)>::from(p) => p,
+			Some(pos) => Point2::from(pos),
+			None => p,
+		}
+	}
+	/// Returns uniformly distributed random pathable point inside of [`playable_area`](Self::playable_area).
+	///
+	/// Makes limited number of attempts to hit pathable tile,
+	/// if all of them failed, returns the closest pathable point to the last random sample.
+	pub fn random_point_in_playable(&self, rng: &mut impl Rng) -> Point2 {
+		const MAX_ATTEMPTS: usize = 100;
+
+		let area = self.playable_area;
+		if area.width() == 0 || area.height() == 0 {
+			return self.closest_pathable(area.center());
+		}
+
+		let mut sample = || {
+			Point2::new(
+				rng.random_range(area.x0 as f32..area.x1 as f32),
+				rng.random_range(area.y0 as f32..area.y1 as f32),
+			)
+		};
+		for _ in 1..MAX_ATTEMPTS {
+			let p = sample();
+			if self.is_pathable_tile(p.to_tile()) {
+				return p;
+			}
+		}
+		self.closest_pathable(sample())
+	}
+	/// Searches for the closest pathable tile in rings around given position.
+	fn closest_pathable_tile(&self, pos: Point2) -> Option<Pos> {
+		let (width, height) = self.pathing_grid.dim();
+		if width == 0 || height == 0 {
+			return None;
+		}
+		let (cx, cy) = (
+			(pos.x.max(0.0) as usize).min(width - 1),
+			(pos.y.max(0.0) as usize).min(height - 1),
+		);
+		if self.is_pathable_tile((cx, cy)) {
+			return Some((cx, cy));
+		}
+
+		let mut best: Option<(Pos, f32)> = None;
+		for r in 1..width.max(height) {
+			// Tiles on further rings can't be closer than found one,
+			// taking into account offset of `pos` inside its tile (up to half of tile)
+			let min_d = r as f32 - 0.5;
+			if best.is_some_and(|(_, d)| d <= min_d * min_d) {
+				break;
+			}
+
+			let x0 = cx.saturating_sub(r);
+			let x1 = (cx + r).min(width - 1);
+			let y0 = cy.saturating_sub(r);
+			let y1 = (cy + r).min(height - 1);
+			for tile in iproduct!(x0..=x1, y0..=y1) {
+				let (x, y) = tile;
+				if x.abs_diff(cx).max(y.abs_diff(cy)) != r || !self.is_pathable_tile(tile) {
+					continue;
+				}
+				let d = pos.distance_squared(Point2::from(tile));
+				if best.is_none_or(|(_, best_d)| d < best_d) {
+					best = Some((tile, d));
+				}
+			}
+		}
+
+		best.map(|(tile, _)| tile)
+	}
+	/// Returns possible start locations of opponent, except given one,
+	/// sorted by length of ground path from it (closest first).
+	///
+	/// Straight distance is used for locations, which can't be reached by ground.
+	pub fn enemy_start_candidates(&self, my_start: Point2) -> Vec<Point2> {
+		let mut candidates = self
+			.start_locations
+			.iter()
+			.filter(|loc| !loc.is_closer(1.0, my_start))
+			.map(|&loc| (loc, self.path_distance(my_start, loc)))
+			.collect::<Vec<_>>();
+		candidates.sort_unstable_by(|(_, d1), (_, d2)| d1.total_cmp(d2));
+		candidates.into_iter().map(|(loc, _)| loc).collect()
+	}
+	/// Returns the furthest possible start location of opponent from given one,
+	/// which is the actual enemy start on 1v1 maps.
+	pub fn likely_enemy_start(&self, my_start: Point2) -> Option<Point2> {
+		self.enemy_start_candidates(my_start).pop()
+	}
+	fn path_distance(&self, start: Point2, goal: Point2) -> f32 {
+		match self.path(start, goal) {
+			Some(path) => path.windows(2).map(|w| w[0].distance(w[1])).sum(),
+			None => start.distance(goal),
+		}
+	}
+	fn is_townhall_placeable(&self, pos: Point2) -> bool {
+		self.can_place(pos.offset(-2.5, -2.5), (5, 5))
+	}
+	/// Checks if all tiles of rectangle with given size are placeable.
+	///
+	/// `top_left` is the corner of rectangle with the smallest coordinates.
+	pub fn can_place(&self, top_left: Point2, size: (usize, usize)) -> bool {
+		if top_left.x < 0.0 || top_left.y < 0.0 {
+			return false;
+		}
+		let (x, y) = <(usize, usize)>::from(top_left);
+		let (w, h) = size;
+		iproduct!(x..x + w, y..y + h).all(|pos| self.placement_grid.get(pos).is_some_and(|p| p.is_empty()))
+	}
+	/// Checks if building produced by given ability can be placed with center on given position.
+	///
+	/// Returns `false` if ability doesn't place a building.
+	pub fn can_place_building(&self, data: &AbilityData, pos: Point2) -> bool {
+		match data.footprint_radius {
+			Some(radius) if data.is_building => {
+				let size = (radius * 2.0).round() as usize;
+				self.can_place(pos.offset(-radius, -radius), (size, size))
+			}
+			_ => false,
+		}
+	}
+	/// Sanity check for position of gas building: checks that 3x3 building centered on it
+	/// is aligned to tiles and fits into [`playable_area`](Self::playable_area).
+	///
+	/// It doesn't check that there's vespene geyser at given position, since geysers aren't known here
+	/// (they aren't placeable in [`placement_grid`](Self::placement_grid) like any other obstacle).
+	/// Pass positions of actual geysers and use [`free_geysers`](Self::free_geysers)
+	/// to also exclude geysers which already have gas building.
+	pub fn is_valid_geyser_position(&self, geyser_pos: Point2) -> bool {
+		if geyser_pos.x.fract() != 0.5 || geyser_pos.y.fract() != 0.5 {
+			return false;
+		}
+		let area = self.playable_area;
+		let (x0, y0) = (geyser_pos.x - 1.5, geyser_pos.y - 1.5);
+		x0 >= area.x0 as f32
+			&& y0 >= area.y0 as f32
+			&& x0 + 3.0 <= area.x1 as f32
+			&& y0 + 3.0 <= area.y1 as f32
+	}
+	/// Returns geysers from given ones, which have valid position and don't have gas building yet.
+	///
+	/// `refineries` are positions of existing gas buildings (your and enemy's),
+	/// they have the same position as geysers they are built on.
+	pub fn free_geysers(&self, geysers: &[Point2], refineries: &[Point2]) -> Vec<Point2> {
+		geysers
+			.iter()
+			.copied()
+			.filter(|&g| self.is_valid_geyser_position(g) && !refineries.iter().any(|&r| r.is_closer(1.0, g)))
+			.collect()
+	}
 }
 impl FromProto<ResponseGameInfo> for GameInfo {
 	#[inline]
@@ -99,12 +756,41 @@ impl FromProto<ResponseGameInfo> for GameInfo {
 				(area_p0_x + (area_p1_x - area_p0_x) / 2) as f32,
 				(area_p0_y + (area_p1_y - area_p0_y) / 2) as f32,
 			),
+			buildable_mask: OnceCell::new(),
 		}
 	}
 }
 
+/// Narrow pathable passage on the map, see [`find_chokepoints`](GameInfo::find_chokepoints).
+#[derive(Debug, Clone)]
+pub struct Chokepoint {
+	/// Center of passage.
+	pub center: Point2,
+	/// Approximate width of passage in the narrowest place.
+	pub width: f32,
+	/// Two opposite unit vectors pointing along passage, i.e. where it opens up.
+	pub directions: [Point2; 2],
+	/// Pathable tiles which belong to passage.
+	pub tiles: Vec<Point2>,
+}
+
+/// Part of pathable area of the map bounded by obstacles and chokepoints,
+/// see [`regions`](GameInfo::regions).
+#[derive(Debug, Clone)]
+pub struct Region {
+	/// Index of region in list of all regions.
+	pub id: usize,
+	/// Pathable tiles of region.
+	pub tiles: Vec<Point2>,
+	/// Tile of region closest to its geometric center.
+	pub center: Point2,
+	/// Ids of regions connected with this one by a chokepoint.
+	pub adjacent: Vec<usize>,
+}
+
 /// Information about player.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PlayerInfo {
 	/// Player id.
 	pub id: u32,
@@ -121,3 +807,133 @@ pub struct PlayerInfo {
 	/// In-game name of player.
 	pub player_name: Option<String>,
 }
+impl PlayerInfo {
+	/// Checks if player is in-game AI.
+	#[inline]
+	pub fn is_computer(&self) -> bool {
+		self.player_type == PlayerType::Computer
+	}
+	/// Checks if player is participant, i.e. human or bot (not in-game AI).
+	#[inline]
+	pub fn is_human(&self) -> bool {
+		self.player_type == PlayerType::Participant
+	}
+}
+
+/// Groups resources (minerals and geysers) which belong to the same expansion,
+/// returning indices of given positions for each group.
+pub(crate) fn group_resources(resources: &[Point2]) -> Vec<Vec<usize>> {
+	const RESOURCE_SPREAD: f32 = 72.25; // 8.5
+
+	let positions = resources
+		.iter()
+		.copied()
+		.zip(0..)
+		.collect::<Vec<(Point2, usize)>>();
+	dbscan(
+		&positions,
+		range_query(
+			&positions,
+			|(p1, _), (p2, _)| p1.distance_squared(*p2),
+			RESOURCE_SPREAD,
+		),
+		1,
+	)
+	.0
+	.into_iter()
+	.map(|group| group.into_iter().map(|(_, i)| i).collect())
+	.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ndarray::s;
+
+	fn info_with_pathable(width: usize, height: usize, pathable: &[(usize, usize)]) -> GameInfo {
+		let mut pathing_grid = PixelMap::from_elem((width, height), Pixel::Set);
+		for &tile in pathable {
+			pathing_grid[tile] = Pixel::Empty;
+		}
+		GameInfo {
+			pathing_grid,
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn closest_pathable_tile_accounts_for_offset_inside_tile() {
+		// Tile (4, 4) is on the first ring, but (7, 5) on the second ring is closer
+		// to the point near the right edge of tile (5, 5)
+		let (far, near): (Pos, Pos) = ((4, 4), (7, 5));
+		let info = info_with_pathable(12, 12, &[far, near]);
+		let pos = Point2::new(5.99, 5.5);
+		assert!(pos.distance_squared(Point2::from(near)) < pos.distance_squared(Point2::from(far)));
+		assert_eq!(info.closest_pathable_tile(pos), Some(near));
+	}
+
+	#[test]
+	fn closest_pathable_tile_returns_own_tile() {
+		let info = info_with_pathable(4, 4, &[(1, 2)]);
+		assert_eq!(info.closest_pathable_tile(Point2::new(1.2, 2.9)), Some((1, 2)));
+		assert_eq!(
+			info_with_pathable(4, 4, &[]).closest_pathable_tile(Point2::new(1.0, 1.0)),
+			None
+		);
+	}
+
+	#[test]
+	fn expansion_locations_include_occupied_main() {
+		let main = Point2::new(10.5, 10.5);
+		let natural = Point2::new(33.5, 10.5);
+		// Main and natural are separated by unbuildable area with minerals of the main on its edge
+		let mut placement_grid = PixelMap::from_elem((48, 24), Pixel::Empty);
+		placement_grid.slice_mut(s![17..24, ..]).fill(Pixel::Set);
+		// Existing townhall
+		placement_grid.slice_mut(s![8..13, 8..13]).fill(Pixel::Set);
+		let mut resources = vec![];
+		for y in 7..14 {
+			let y = y as f32 + 0.5;
+			resources.push(Point2::new(17.0, y));
+			resources.push(Point2::new(40.0, y));
+			placement_grid[(39, y as usize)] = Pixel::Set;
+			placement_grid[(40, y as usize)] = Pixel::Set;
+		}
+		let mut info = GameInfo {
+			placement_grid,
+			..Default::default()
+		};
+
+		let mut locations = info.expansion_locations(&resources, &[main]);
+		locations.sort_by(|a, b| a.x.total_cmp(&b.x));
+		assert_eq!(locations, [main, natural]);
+
+		assert_eq!(info.expansion_locations(&resources, &[]), [natural]);
+
+		info.start_locations = vec![main];
+		let mut locations = info.expansion_locations(&resources, &[]);
+		locations.sort_by(|a, b| a.x.total_cmp(&b.x));
+		assert_eq!(locations, [main, natural]);
+	}
+
+	#[test]
+	fn distance_field_goes_around_obstacles() {
+		let pathable = iproduct!(0..4, 0..3)
+			.filter(|&(x, y)| !(x == 1 && y < 2))
+			.collect::<Vec<_>>();
+		let info = info_with_pathable(4, 3, &pathable);
+		let field = info.distance_field(&[Point2::new(0.5, 0.5)]);
+		assert_eq!(field.len(), 12);
+
+		let at = |x: f32, y: f32| info.distance_at(&field, Point2::new(x, y));
+		assert_eq!(at(0.5, 0.5), 0);
+		assert_eq!(at(0.5, 2.5), 2);
+		// Corners aren't cut around the wall
+		assert_eq!(at(1.5, 2.5), 3);
+		assert_eq!(at(2.5, 1.5), 5);
+		assert_eq!(at(3.5, 0.5), 6);
+		assert_eq!(at(1.5, 0.5), u32::MAX);
+		assert_eq!(at(-0.5, 0.5), u32::MAX);
+		assert_eq!(at(4.5, 0.5), u32::MAX);
+	}
+}
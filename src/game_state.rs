@@ -12,7 +12,7 @@ use crate::{
 	units::Units,
 };
 use num_traits::FromPrimitive;
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 use sc2_proto::{
 	query::RequestQueryAvailableAbilities,
 	raw::{Alliance as ProtoAlliance, PowerSource as ProtoPowerSource},
@@ -184,11 +184,20 @@ where
 	raw.dead_units = dead_units;
 
 	// Upgrades
-	*raw.upgrades.write_lock() = raw_player
+	let upgrades = raw_player
 		.upgrade_ids
 		.iter()
 		.map(|u| UpgradeId::from_u32(*u).unwrap_or_else(|| panic!("There's no `UpgradeId` with value {}", u)))
 		.collect::<FxHashSet<_>>();
+	{
+		let mut current_upgrades = raw.upgrades.write_lock();
+		for u in &upgrades {
+			if !current_upgrades.contains(u) {
+				events.push(Event::UpgradeComplete(*u));
+			}
+		}
+		*current_upgrades = upgrades;
+	}
 
 	// Map
 	let map_state = res_raw.map_state.deref();
@@ -270,6 +279,52 @@ where
 		bot.under_construction.remove(&tag);
 	}
 
+	let invisible_enemies = bot
+		.units
+		.enemy
+		.all
+		.iter()
+		.map(|u| (u.tag(), u.is_invisible()))
+		.collect::<FxHashMap<_, _>>();
+	for (tag, is_invisible) in &invisible_enemies {
+		match bot.invisible_enemies.get(tag) {
+			Some(true) if !is_invisible => events.push(Event::EnemyRevealed(*tag)),
+			Some(false) if *is_invisible => events.push(Event::EnemyCloaked(*tag)),
+			_ => {}
+		}
+	}
+	bot.invisible_enemies = invisible_enemies;
+
+	// Snapshots are only memory of units, so they aren't considered visible
+	let visible_enemies = bot
+		.units
+		.enemy
+		.all
+		.iter()
+		.filter(|u| u.is_visible())
+		.map(|u| u.tag())
+		.collect::<FxHashSet<_>>();
+	for tag in visible_enemies.difference(&bot.visible_enemies) {
+		events.push(Event::EnemySpotted(*tag));
+	}
+	let dead_units = &bot.state.observation.raw.dead_units;
+	for tag in bot.visible_enemies.difference(&visible_enemies) {
+		if !dead_units.contains(tag) {
+			events.push(Event::EnemyLost(*tag));
+		}
+	}
+	bot.visible_enemies = visible_enemies;
+
+	for u in &bot.units.all {
+		let damage = u.damage_taken();
+		if damage > 0 {
+			events.push(Event::UnitDamaged {
+				tag: u.tag(),
+				amount: damage as f32,
+			});
+		}
+	}
+
 	if bot.enemy_race.is_random()
 		&& let Some(race) = bot
 			.units
@@ -283,6 +338,12 @@ where
 		bot.enemy_race = race;
 	}
 
+	events.extend(bot.state.action_errors.iter().map(|e| Event::ActionError {
+		unit_tag: (e.unit != 0).then_some(e.unit),
+		ability: e.ability,
+		result: e.result,
+	}));
+
 	Ok(events)
 }
 
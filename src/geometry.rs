@@ -2,8 +2,15 @@
 //!
 //! Countains various geometric primitives with useful helper methods.
 
-use crate::{FromProto, IntoProto, distance::Distance, unit::Radius};
+use crate::{
+	FromProto, IntoProto,
+	distance::Distance,
+	pixel_map::{ByteMap, ByteMapExt, PixelMap},
+	unit::Radius,
+};
 use sc2_proto::common::{Point, Point2D};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::{
 	hash::{Hash, Hasher},
 	iter::Sum,
@@ -13,6 +20,7 @@ use std::{
 /// Size of 2D rectangle.
 #[allow(missing_docs)]
 #[derive(Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Size {
 	pub x: usize,
 	pub y: usize,
@@ -25,8 +33,12 @@ impl Size {
 }
 
 /// Rectangle from (x0, y0) to (x1, y1).
+///
+/// Lower edges are inclusive and upper edges are exclusive,
+/// i.e. rectangle consists of tiles in ranges `x0..x1` and `y0..y1`.
 #[allow(missing_docs)]
 #[derive(Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Rect {
 	pub x0: usize,
 	pub y0: usize,
@@ -38,11 +50,102 @@ impl Rect {
 	pub fn new(x0: usize, y0: usize, x1: usize, y1: usize) -> Self {
 		Self { x0, y0, x1, y1 }
 	}
+	/// Width of rectangle.
+	pub fn width(&self) -> usize {
+		self.x1.saturating_sub(self.x0)
+	}
+	/// Height of rectangle.
+	pub fn height(&self) -> usize {
+		self.y1.saturating_sub(self.y0)
+	}
+	/// Number of tiles in rectangle.
+	pub fn area(&self) -> usize {
+		self.width() * self.height()
+	}
+	/// Iterates over coordinates of all tiles in rectangle, column by column.
+	pub fn tiles(&self) -> impl Iterator<Item = (usize, usize)> + use<> {
+		iproduct!(self.x0..self.x1, self.y0..self.y1)
+	}
+	/// Returns overlapping part of two rectangles, `None` if they don't overlap.
+	pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+		let rect = Rect::new(
+			self.x0.max(other.x0),
+			self.y0.max(other.y0),
+			self.x1.min(other.x1),
+			self.y1.min(other.y1),
+		);
+		(rect.area() > 0).then_some(rect)
+	}
+	/// Returns center of rectangle.
+	pub fn center(&self) -> Point2 {
+		Point2::new((self.x0 + self.x1) as f32 / 2.0, (self.y0 + self.y1) as f32 / 2.0)
+	}
+	/// Checks if given point is inside of rectangle.
+	pub fn contains(&self, p: Point2) -> bool {
+		(self.x0 as f32) <= p.x && p.x < self.x1 as f32 && (self.y0 as f32) <= p.y && p.y < self.y1 as f32
+	}
+	/// Returns the closest to given point position inside of rectangle.
+	///
+	/// Since upper edges are exclusive, coordinates are clamped
+	/// to the largest values which are less than `x1` and `y1`.
+	pub fn clamp(&self, p: Point2) -> Point2 {
+		Point2::new(
+			p.x.clamp(self.x0 as f32, (self.x1 as f32).next_down().max(self.x0 as f32)),
+			p.y.clamp(self.y0 as f32, (self.y1 as f32).next_down().max(self.y0 as f32)),
+		)
+	}
+}
+
+/// Integer coordinates of grid tile, used as key of per-tile data.
+///
+/// Unlike [`Point2`], which compares and hashes by truncated coordinates,
+/// this makes tile granularity explicit in types.
+/// Grids can be indexed with it directly.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TilePos(pub usize, pub usize);
+impl TilePos {
+	/// Iterates over up to 8 tiles adjacent by side or corner,
+	/// skipping ones with negative coordinates.
+	pub fn neighbors(self) -> impl Iterator<Item = TilePos> {
+		let TilePos(x, y) = self;
+		iproduct!([-1, 0, 1], [-1, 0, 1])
+			.filter(|&offset| offset != (0, 0))
+			.filter_map(move |(dx, dy)| Some(TilePos(x.checked_add_signed(dx)?, y.checked_add_signed(dy)?)))
+	}
+}
+impl From<Point2> for TilePos {
+	/// Returns tile containing given point, see [`Point2::to_tile`].
+	#[inline]
+	fn from(p: Point2) -> Self {
+		let (x, y) = p.to_tile();
+		Self(x, y)
+	}
+}
+impl From<TilePos> for Point2 {
+	/// Returns center of tile.
+	#[inline]
+	fn from(TilePos(x, y): TilePos) -> Self {
+		Self::from((x, y))
+	}
+}
+impl From<(usize, usize)> for TilePos {
+	#[inline]
+	fn from((x, y): (usize, usize)) -> Self {
+		Self(x, y)
+	}
+}
+impl From<TilePos> for (usize, usize) {
+	#[inline]
+	fn from(TilePos(x, y): TilePos) -> Self {
+		(x, y)
+	}
 }
 
 /// Point on 2D grid, the most frequently used geometric primitive.
 #[allow(missing_docs)]
 #[derive(Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Point2 {
 	pub x: f32,
 	pub y: f32,
@@ -55,8 +158,14 @@ impl Point2 {
 		Self { x, y }
 	}
 	/// Returns new point with offset towards `other` on given distance.
+	///
+	/// Returns `self` if `other` is the same point, since direction is undefined.
 	pub fn towards(self, other: Self, offset: f32) -> Self {
-		self + (other - self) / self.distance(other) * offset
+		let distance = self.distance(other);
+		if distance == 0.0 {
+			return self;
+		}
+		self + (other - self) / distance * offset
 	}
 	/// Returns new point with offset towards given angle on given distance.
 	pub fn towards_angle(self, angle: f32, offset: f32) -> Self {
@@ -102,11 +211,36 @@ impl Point2 {
 	pub fn len(self) -> f32 {
 		self.len_squared().sqrt()
 	}
+	/// Returns squared length of the vector, same as [`len_squared`](Self::len_squared).
+	#[inline]
+	pub fn length_squared(self) -> f32 {
+		self.len_squared()
+	}
+	/// Returns length of the vector, same as [`len`](Self::len).
+	#[inline]
+	pub fn length(self) -> f32 {
+		self.len()
+	}
 	/// Normalizes the vector.
+	///
+	/// Zero vector stays zero, since it has no direction.
 	pub fn normalize(self) -> Self {
-		self / self.len()
+		let len = self.len();
+		if len == 0.0 {
+			return self;
+		}
+		self / len
+	}
+	/// Constructs vector of given length pointing at given angle.
+	///
+	/// Angles are in radians, counterclockwise from positive `x` axis.
+	pub fn from_angle(angle: f32, len: f32) -> Self {
+		let (s, c) = angle.sin_cos();
+		Self::new(c * len, s * len)
 	}
-	/// Rotates the vector on given angle.
+	/// Rotates the vector on given angle around `(0, 0)`.
+	///
+	/// Positive angles rotate counterclockwise, negative clockwise.
 	pub fn rotate(self, angle: f32) -> Self {
 		let (s, c) = angle.sin_cos();
 		let (x, y) = (self.x, self.y);
@@ -115,6 +249,20 @@ impl Point2 {
 			y: s * x + c * y,
 		}
 	}
+	/// Rotates the point on given angle around `center`.
+	///
+	/// Positive angles rotate counterclockwise, negative clockwise.
+	pub fn rotate_around(self, center: Self, angle: f32) -> Self {
+		center + (self - center).rotate(angle)
+	}
+	/// Returns angle of the vector from `self` to `other`.
+	///
+	/// Angle is in radians, counterclockwise from positive `x` axis, in range `-π..=π`.
+	/// It's consistent with [`towards_angle`](Self::towards_angle) and [`from_angle`](Self::from_angle).
+	pub fn angle_to(self, other: Self) -> f32 {
+		let v = other - self;
+		v.y.atan2(v.x)
+	}
 	/// Fast rotation of the vector on 90 degrees.
 	pub fn rotate90(self, clockwise: bool) -> Self {
 		if clockwise {
@@ -191,6 +339,23 @@ impl Point2 {
 	pub fn as_tuple(self) -> (f32, f32) {
 		(self.x, self.y)
 	}
+	/// Returns indices of grid tile which contains this point.
+	///
+	/// Coordinates are floored, negative coordinates become `0`.
+	/// Result isn't bounded by map size, so it should be checked before indexing grids
+	/// (e.g. with `get`).
+	pub fn to_tile(self) -> (usize, usize) {
+		(self.x.floor() as usize, self.y.floor() as usize)
+	}
+	/// Returns center of grid tile with given indices, i.e. `(x + 0.5, y + 0.5)`.
+	///
+	/// `Point2::from_tile(p.to_tile())` is the center of tile containing `p`.
+	pub fn from_tile((x, y): (usize, usize)) -> Self {
+		Self {
+			x: x as f32 + 0.5,
+			y: y as f32 + 0.5,
+		}
+	}
 	/// Converts 2D Point to 3D Point using given `z` value.
 	pub fn to3(self, z: f32) -> Point3 {
 		Point3 {
@@ -199,6 +364,13 @@ impl Point2 {
 			z,
 		}
 	}
+	/// Converts 2D Point to 3D Point placed on the ground,
+	/// using given terrain height map (e.g. [`terrain_height`](crate::game_info::GameInfo::terrain_height)).
+	///
+	/// Height is interpolated between tiles, so debug shapes drawn there sit on terrain.
+	pub fn to_ground(self, height: &ByteMap) -> Point3 {
+		self.to3(height.height_at(self))
+	}
 }
 
 impl PartialEq for Point2 {
@@ -215,6 +387,16 @@ impl Hash for Point2 {
 	}
 }
 
+impl From<Size> for Point2 {
+	/// Converts size to the far corner of rectangle starting at `(0, 0)`.
+	#[inline]
+	fn from(size: Size) -> Self {
+		Self {
+			x: size.x as f32,
+			y: size.y as f32,
+		}
+	}
+}
 impl From<&Point2> for Point2 {
 	#[inline]
 	fn from(p: &Point2) -> Self {
@@ -432,6 +614,7 @@ impl IntoProto<Point2D> for Point2 {
 /// Point in 3D game world.
 #[allow(missing_docs)]
 #[derive(Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Point3 {
 	pub x: f32,
 	pub y: f32,
@@ -605,3 +788,112 @@ impl Radius for Point2 {}
 impl Radius for &Point2 {}
 impl Radius for Point3 {}
 impl Radius for &Point3 {}
+
+/// Checks if straight line between tiles containing `a` and `b` doesn't cross any `Set` tile of given grid.
+///
+/// Tiles are walked with Bresenham's line algorithm, both ends are included.
+/// Tiles out of grid bounds are considered blocked.
+pub fn line_of_sight(grid: &PixelMap, a: Point2, b: Point2) -> bool {
+	let is_free = |x: isize, y: isize| {
+		x >= 0 && y >= 0 && grid.get((x as usize, y as usize)).is_some_and(|p| p.is_empty())
+	};
+
+	let (mut x, mut y) = (a.x.floor() as isize, a.y.floor() as isize);
+	let (x1, y1) = (b.x.floor() as isize, b.y.floor() as isize);
+	let (dx, dy) = ((x1 - x).abs(), -(y1 - y).abs());
+	let (sx, sy) = ((x1 - x).signum(), (y1 - y).signum());
+	let mut err = dx + dy;
+	loop {
+		if !is_free(x, y) {
+			return false;
+		}
+		if x == x1 && y == y1 {
+			return true;
+		}
+		let e2 = 2 * err;
+		if e2 >= dy {
+			err += dy;
+			x += sx;
+		}
+		if e2 <= dx {
+			err += dx;
+			y += sy;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+	const EPS: f32 = 1e-5;
+
+	fn assert_close(a: Point2, b: Point2) {
+		assert!(
+			(a.x - b.x).abs() < EPS && (a.y - b.y).abs() < EPS,
+			"{:?} != {:?}",
+			a,
+			b
+		);
+	}
+
+	#[test]
+	fn angle_to_quadrants() {
+		let o = Point2::new(1.0, 1.0);
+		let table = [
+			(Point2::new(2.0, 1.0), 0.0),
+			(Point2::new(2.0, 2.0), FRAC_PI_4),
+			(Point2::new(1.0, 2.0), FRAC_PI_2),
+			(Point2::new(0.0, 2.0), 3.0 * FRAC_PI_4),
+			(Point2::new(0.0, 1.0), PI),
+			(Point2::new(0.0, 0.0), -3.0 * FRAC_PI_4),
+			(Point2::new(1.0, 0.0), -FRAC_PI_2),
+			(Point2::new(2.0, 0.0), -FRAC_PI_4),
+		];
+		for (p, angle) in table {
+			assert!((o.angle_to(p) - angle).abs() < EPS, "{:?}", p);
+		}
+	}
+
+	#[test]
+	fn rotation_is_counterclockwise() {
+		let v = Point2::new(1.0, 0.0);
+		assert_close(v.rotate(FRAC_PI_2), Point2::new(0.0, 1.0));
+		assert_close(v.rotate(PI), Point2::new(-1.0, 0.0));
+		assert_close(v.rotate(-FRAC_PI_2), Point2::new(0.0, -1.0));
+		assert_close(v.rotate(FRAC_PI_2), v.rotate90(false));
+
+		let center = Point2::new(5.0, 5.0);
+		assert_close(
+			Point2::new(7.0, 5.0).rotate_around(center, FRAC_PI_2),
+			Point2::new(5.0, 7.0),
+		);
+	}
+
+	#[test]
+	fn from_angle_quadrants() {
+		assert_close(Point2::from_angle(0.0, 2.0), Point2::new(2.0, 0.0));
+		assert_close(Point2::from_angle(FRAC_PI_2, 2.0), Point2::new(0.0, 2.0));
+		assert_close(Point2::from_angle(PI, 2.0), Point2::new(-2.0, 0.0));
+		assert_close(Point2::from_angle(-FRAC_PI_2, 2.0), Point2::new(0.0, -2.0));
+
+		let o = Point2::new(3.0, -1.0);
+		for angle in [0.5, 2.0, -0.5, -2.0] {
+			assert!((o.angle_to(o + Point2::from_angle(angle, 1.5)) - angle).abs() < EPS);
+		}
+	}
+
+	#[test]
+	fn length_normalize_and_towards() {
+		let v = Point2::new(3.0, -4.0);
+		assert_eq!(v.length_squared(), 25.0);
+		assert_eq!(v.length(), 5.0);
+		assert_close(v.normalize(), Point2::new(0.6, -0.8));
+		assert_close(Point2::default().normalize(), Point2::default());
+
+		let p = Point2::new(1.0, 1.0);
+		assert_close(p.towards(p + v, 2.5), Point2::new(2.5, -1.0));
+		assert_close(p.towards(p, 2.5), p);
+	}
+}
@@ -60,6 +60,47 @@ impl UnitTypeId {
 	}
 	#[rustfmt::skip::macros(matches)]
 	#[inline]
+	pub fn is_flying(self) -> bool {
+		matches!(
+			self,
+			UnitTypeId::Overlord
+				| UnitTypeId::OverlordTransport
+				| UnitTypeId::OverlordCocoon
+				| UnitTypeId::TransportOverlordCocoon
+				| UnitTypeId::Overseer
+				| UnitTypeId::OverseerSiegeMode
+				| UnitTypeId::Mutalisk
+				| UnitTypeId::Corruptor
+				| UnitTypeId::BroodLord
+				| UnitTypeId::Viper
+				| UnitTypeId::LocustMPFlying
+				| UnitTypeId::Medivac
+				| UnitTypeId::VikingFighter
+				| UnitTypeId::Liberator
+				| UnitTypeId::LiberatorAG
+				| UnitTypeId::Banshee
+				| UnitTypeId::Raven
+				| UnitTypeId::Battlecruiser
+				| UnitTypeId::CommandCenterFlying
+				| UnitTypeId::OrbitalCommandFlying
+				| UnitTypeId::BarracksFlying
+				| UnitTypeId::FactoryFlying
+				| UnitTypeId::StarportFlying
+				| UnitTypeId::Observer
+				| UnitTypeId::ObserverSiegeMode
+				| UnitTypeId::WarpPrism
+				| UnitTypeId::WarpPrismPhasing
+				| UnitTypeId::Phoenix
+				| UnitTypeId::VoidRay
+				| UnitTypeId::Oracle
+				| UnitTypeId::Tempest
+				| UnitTypeId::Carrier
+				| UnitTypeId::Interceptor
+				| UnitTypeId::Mothership
+		)
+	}
+	#[rustfmt::skip::macros(matches)]
+	#[inline]
 	pub fn is_structure(self) -> bool {
 		matches!(
 			self,
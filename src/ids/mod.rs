@@ -15,3 +15,5 @@ pub use unit_typeid::UnitTypeId;
 pub use upgrade_id::UpgradeId;
 
 mod impls;
+#[cfg(feature = "serde")]
+pub mod numeric;
@@ -0,0 +1,40 @@
+//! Serde helpers to encode ids as their numeric values instead of variant names.
+//!
+//! Numeric form is smaller and stays stable when variants are renamed in game updates.
+//! Works with any id enum, use it on fields with `#[serde(with = "sc2::ids::numeric")]`:
+//! ```
+//! # use serde::{Deserialize, Serialize};
+//! # use sc2::ids::UnitTypeId;
+//! #[derive(Serialize, Deserialize)]
+//! struct Production {
+//! 	#[serde(with = "sc2::ids::numeric")]
+//! 	unit: UnitTypeId,
+//! 	count: u32,
+//! }
+//! ```
+//! Functions can also be used separately in `serialize_with` and `deserialize_with` attributes.
+
+use num_traits::{FromPrimitive, ToPrimitive};
+use serde::{Deserialize, Deserializer, Serializer, de, ser};
+
+/// Serializes id as `u32`.
+pub fn serialize<T, S>(id: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+	T: ToPrimitive,
+	S: Serializer,
+{
+	let value = id
+		.to_u32()
+		.ok_or_else(|| ser::Error::custom("id doesn't fit into u32"))?;
+	serializer.serialize_u32(value)
+}
+
+/// Deserializes id from `u32`, fails if there's no id with such value.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+	T: FromPrimitive,
+	D: Deserializer<'de>,
+{
+	let value = u32::deserialize(deserializer)?;
+	T::from_u32(value).ok_or_else(|| de::Error::custom(format!("there's no id with value {}", value)))
+}
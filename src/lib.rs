@@ -62,6 +62,8 @@ fn main() -> SC2Result<()> {
 		//     sc2_version: Option<&str>, // Default: None - Latest available patch.
 		//     save_replay_as: Option<&str>, // Default: None - Doesn't save replay.
 		//     realtime: bool, // Default: false
+		//     step_size: Option<u32>, // Default: None - Bot's game step is used.
+		//     disable_fog: bool, // Default: false
 		// }
 		LaunchOptions::default(),
 	)
@@ -348,15 +350,17 @@ pub mod prelude {
 		action::Target,
 		bot::PlacementOptions,
 		client::{
-			LaunchOptions, RunnerMulti, RunnerSingle, SC2Result, run_ladder_game, run_vs_computer,
-			run_vs_human,
+			GameSetup, LaunchOptions, RunnerMulti, RunnerSingle, SC2Result, StepError, run_game_setup,
+			run_ladder_game, run_replay, run_vs_computer, run_vs_human,
 		},
 		consts::{ALL_PRODUCERS, PRODUCERS, RESEARCHERS, TECH_REQUIREMENTS},
 		distance::{Center, Distance, DistanceIterator, DistanceSlice},
 		game_state::Alliance,
 		geometry::Point2,
 		ids::*,
+		pixel_map::{ByteMapExt, Connectivity, Grid, PixelMapExt},
 		player::{AIBuild, Computer, Difficulty, GameResult, Race},
+		time::GameTime,
 		unit::Unit,
 		units::{Units, iter::UnitsIterator},
 	};
@@ -382,11 +386,15 @@ pub mod pixel_map;
 pub mod player;
 pub mod ramp;
 pub mod score;
+pub mod threat_map;
+pub mod time;
 pub mod unit;
 pub mod units;
 pub mod utils;
 
+use action::ActionResult;
 use game_state::Alliance;
+use ids::{AbilityId, UpgradeId};
 use player::{GameResult, Race};
 
 /**
@@ -488,6 +496,20 @@ impl DerefMut for MyBot {
 	}
 }
 ```
+
+# Derives
+
+Attributes of your struct are kept, so derives apply to the resulting struct including `_bot` field.
+Since [`Bot`] implements [`Default`], `#[derive(Default)]` works as expected:
+```
+#[bot]
+#[derive(Default)]
+struct MyBot {
+	field: Type,
+}
+```
+
+[`Bot`] isn't [`Clone`] (it owns SC2 process and connection to it), so `#[derive(Clone)]` fails to compile.
 */
 #[doc(inline)]
 pub use sc2_macro::bot;
@@ -495,6 +517,9 @@ pub use sc2_macro::bot;
 /**
 `#[bot_new]` macro adds initialization of field added by [`#[bot]`](macro@bot) macro.
 
+Constructor must return struct literal, which also may be wrapped in a block or `return` statement,
+otherwise compile error is emitted.
+
 Usage:
 ```
 #[bot]
@@ -632,15 +657,20 @@ pub use sc2_proto::sc2api::Request;
 /// if `raw_crop_to_playable_area` is `true`, maps will be crooped to the size of
 /// [`self.game_info.playable_area`](game_info::GameInfo::playable_area).
 ///
+/// if `random_seed` is set, game will be created with this seed, so games are reproducible.
+/// It's used only when bot creates the game (i.e. not on ladder).
+///
 /// Defaults:
 /// `name`: `None`
 /// `raw_affects_selection`: `false`
 /// `raw_crop_to_playable_area`: `false`
+/// `random_seed`: `None`
 pub struct PlayerSettings<'a> {
 	pub race: Race,
 	pub name: Option<&'a str>,
 	pub raw_affects_selection: bool,
 	pub raw_crop_to_playable_area: bool,
+	pub random_seed: Option<u32>,
 }
 impl<'a> PlayerSettings<'a> {
 	/// Constructs new settings with given `Race`.
@@ -650,6 +680,7 @@ impl<'a> PlayerSettings<'a> {
 			name: None,
 			raw_affects_selection: false,
 			raw_crop_to_playable_area: false,
+			random_seed: None,
 		}
 	}
 	/// Sets name of the player.
@@ -667,6 +698,11 @@ impl<'a> PlayerSettings<'a> {
 		self.raw_crop_to_playable_area = val;
 		self
 	}
+	/// Sets random seed of the game.
+	pub fn with_seed(mut self, seed: u32) -> Self {
+		self.random_seed = Some(seed);
+		self
+	}
 }
 impl Default for PlayerSettings<'_> {
 	fn default() -> Self {
@@ -675,6 +711,7 @@ impl Default for PlayerSettings<'_> {
 			name: None,
 			raw_affects_selection: false,
 			raw_crop_to_playable_area: false,
+			random_seed: None,
 		}
 	}
 }
@@ -693,6 +730,43 @@ pub enum Event {
 	ConstructionComplete(u64),
 	/// Detected actual race of random opponent.
 	RandomRaceDetected(Race),
+	/// Research of upgrade finished (your only).
+	UpgradeComplete(UpgradeId),
+	/// Unit lost health or shield since previous step (all visible units: your, enemy, neutral).
+	///
+	/// `amount` is total damage taken between observations, so with bigger game step
+	/// damage from multiple frames is aggregated into a single event.
+	/// Newly seen units (without previous observation) and healed units don't fire this event.
+	UnitDamaged {
+		/// Tag of damaged unit.
+		tag: u64,
+		/// Amount of health and shield lost.
+		amount: f32,
+	},
+	/// Cloaked enemy unit got detected.
+	EnemyRevealed(u64),
+	/// Visible enemy unit cloaked or went out of detection, but is still seen as cloaked shape.
+	///
+	/// Enemies which appeared already cloaked don't fire this event.
+	/// Undetected burrowed units aren't reported by API at all,
+	/// so burrowing fires [`EnemyLost`](Self::EnemyLost) instead,
+	/// and detecting burrowed unit fires [`EnemySpotted`](Self::EnemySpotted).
+	EnemyCloaked(u64),
+	/// Enemy unit became visible, including units which were only remembered as snapshots.
+	EnemySpotted(u64),
+	/// Visible enemy unit went out of vision (i.e. became snapshot or disappeared), but didn't die.
+	EnemyLost(u64),
+	/// Command given on previous step was rejected by the game (your only).
+	///
+	/// Same errors are also stored in [`state.action_errors`](game_state::GameState::action_errors).
+	ActionError {
+		/// Tag of unit that was given the command, `None` if game didn't report it.
+		unit_tag: Option<u64>,
+		/// Ability used in the command.
+		ability: AbilityId,
+		/// Reason of failure.
+		result: ActionResult,
+	},
 }
 
 /// Trait that bots must implement.
@@ -700,6 +774,11 @@ pub trait Player {
 	/// Returns settings used to connect bot to the game.
 	fn get_player_settings(&'_ self) -> PlayerSettings<'_>;
 	/// Called once on first step (i.e on game start).
+	///
+	/// At this point [`game_info`](bot::Bot::game_info), [`game_data`](bot::Bot::game_data)
+	/// and state of the first observation are already populated, and events of the first step are handled,
+	/// but [`on_step`](Self::on_step) isn't called yet.
+	/// It's the right place for one-time setup, e.g. computing expansion locations.
 	fn on_start(&mut self) -> SC2Result<()> {
 		Ok(())
 	}
@@ -708,6 +787,11 @@ pub trait Player {
 		Ok(())
 	}
 	/// Called once on last step with a result for your bot.
+	///
+	/// Called whenever game ends on its own: by victory, defeat or tie,
+	/// including endings triggered with [`debug.end_game`](debug::Debugger::end_game) and
+	/// [`debug.win_game`](debug::Debugger::win_game). Not called if bot left the game
+	/// with [`leave`](bot::Bot::leave).
 	fn on_end(&self, _result: GameResult) -> SC2Result<()> {
 		Ok(())
 	}
@@ -717,14 +801,26 @@ pub trait Player {
 	}
 }
 
-trait FromProto<T>
+/// Conversion from protobuf message (or its part) to the type of this crate.
+///
+/// It's the main way data received from the API is turned into [`Unit`](unit::Unit),
+/// [`GameInfo`](game_info::GameInfo) and other types. Implement it for your own types to reuse the same
+/// machinery when working with raw responses (e.g. sent with [`API::send`](api::API::send)).
+///
+/// Conversion must not fail, use [`TryFromProto`] if input may be invalid.
+pub trait FromProto<T>
 where
 	Self: Sized,
 {
+	/// Converts protobuf value to `Self`.
 	fn from_proto(p: T) -> Self;
 }
 
-trait IntoSC2<T> {
+/// Convenience counterpart of [`FromProto`] called on protobuf value, like [`Into`] for [`From`].
+///
+/// Implemented automatically for every type implementing [`FromProto`], so shouldn't be implemented manually.
+pub trait IntoSC2<T> {
+	/// Converts protobuf value to type of this crate.
 	fn into_sc2(self) -> T;
 }
 impl<T, U: FromProto<T>> IntoSC2<U> for T {
@@ -733,14 +829,20 @@ impl<T, U: FromProto<T>> IntoSC2<U> for T {
 	}
 }
 
-trait TryFromProto<T>
+/// Fallible conversion from protobuf message to the type of this crate.
+///
+/// Returns `None` when value can't be represented, e.g. has unknown id.
+pub trait TryFromProto<T>
 where
 	Self: Sized,
 {
+	/// Tries to convert protobuf value to `Self`.
 	fn try_from_proto(p: T) -> Option<Self>;
 }
 
-trait IntoProto<T> {
+/// Conversion from type of this crate to protobuf message, used to build requests to the API.
+pub trait IntoProto<T> {
+	/// Converts `self` to protobuf value.
 	fn into_proto(self) -> T;
 }
 
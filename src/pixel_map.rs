@@ -1,10 +1,20 @@
 //! Data structures, used to store map data.
 #![allow(missing_docs)]
 
-use crate::{FromProto, geometry::Point2};
-use ndarray::Array2;
+use crate::{
+	FromProto, TryFromProto,
+	geometry::{Point2, Size, TilePos},
+};
+#[cfg(feature = "image")]
+use image::{GrayImage, ImageFormat, ImageResult, Luma};
+use ndarray::{Array2, Zip};
 use num_traits::FromPrimitive;
+use rustc_hash::FxHashSet;
 use sc2_proto::common::ImageData;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "image")]
+use std::path::Path;
 use std::{
 	fmt,
 	ops::{Deref, Index, IndexMut},
@@ -31,54 +41,557 @@ impl<T> IndexMut<Point2> for Array2<T> {
 		&mut self[<(usize, usize)>::from(pos)]
 	}
 }
+impl<T> Index<TilePos> for Array2<T> {
+	type Output = T;
+
+	#[inline]
+	fn index(&self, TilePos(x, y): TilePos) -> &Self::Output {
+		&self[(x, y)]
+	}
+}
+impl<T> IndexMut<TilePos> for Array2<T> {
+	#[inline]
+	fn index_mut(&mut self, TilePos(x, y): TilePos) -> &mut Self::Output {
+		&mut self[(x, y)]
+	}
+}
+
+/// Common interface of 2D grids, used to write algorithms working with any map.
+///
+/// Implemented for [`PixelMap`] (value is `true` if pixel is `Set`) and [`ByteMap`].
+pub trait Grid {
+	/// Type of values stored in grid.
+	type Value;
+
+	/// Returns size of grid.
+	fn size(&self) -> Size;
+	/// Returns value at given tile, or `None` if it's out of bounds.
+	fn cell(&self, x: usize, y: usize) -> Option<Self::Value>;
+	/// Checks if given point is inside of grid.
+	fn in_bounds(&self, p: Point2) -> bool {
+		let size = self.size();
+		p.x >= 0.0 && p.y >= 0.0 && p.x < size.x as f32 && p.y < size.y as f32
+	}
+}
+impl Grid for PixelMap {
+	type Value = bool;
+
+	#[inline]
+	fn size(&self) -> Size {
+		let (x, y) = self.dim();
+		Size::new(x, y)
+	}
+	#[inline]
+	fn cell(&self, x: usize, y: usize) -> Option<Self::Value> {
+		self.get((x, y)).map(|pixel| pixel.is_set())
+	}
+}
+impl Grid for ByteMap {
+	type Value = u8;
+
+	#[inline]
+	fn size(&self) -> Size {
+		let (x, y) = self.dim();
+		Size::new(x, y)
+	}
+	#[inline]
+	fn cell(&self, x: usize, y: usize) -> Option<Self::Value> {
+		self.get((x, y)).copied()
+	}
+}
+
+/// Additional methods for [`PixelMap`].
+///
+/// Tiles are called free when their pixel is `Empty` (e.g. pathable or buildable)
+/// and obstacles when it's `Set`.
+pub trait PixelMapExt {
+	/// Returns centers of free tiles within given circle.
+	fn tiles_in_radius(&self, center: Point2, radius: f32) -> impl Iterator<Item = Point2> + '_;
+	/// Returns number of free tiles within given circle, i.e. number of [`tiles_in_radius`](Self::tiles_in_radius).
+	fn count_free_in_radius(&self, center: Point2, radius: f32) -> usize;
+	/// Sets pixel at given position to `Set` if `value` is `true`, or to `Empty` otherwise.
+	/// Does nothing if position is out of bounds.
+	fn set_pixel(&mut self, pos: Point2, value: bool);
+	/// Returns `true` if pixel at given position is `Set`, `None` if position is out of bounds.
+	fn get_pixel(&self, pos: Point2) -> Option<bool>;
+	/// Returns map where pixels are `Set` if they're set in any of two maps.
+	///
+	/// # Panics
+	/// If dimensions of maps are different.
+	fn union(&self, other: &PixelMap) -> PixelMap;
+	/// Returns map where pixels are `Set` if they're set in both maps.
+	///
+	/// # Panics
+	/// If dimensions of maps are different.
+	fn intersect(&self, other: &PixelMap) -> PixelMap;
+	/// Returns map where pixels are `Set` if they're set in this map, but not in other.
+	///
+	/// # Panics
+	/// If dimensions of maps are different.
+	fn difference(&self, other: &PixelMap) -> PixelMap;
+	/// Returns all free (i.e. `Empty`) tiles connected to the tile containing `start`,
+	/// empty set if this tile isn't free or out of bounds.
+	///
+	/// Tiles are connected if they're adjacent by side ([`Connectivity::Four`]),
+	/// or also by corner ([`Connectivity::Eight`]).
+	fn flood_fill(&self, start: Point2, connectivity: Connectivity) -> FxHashSet<Point2>;
+	/// Splits all free (i.e. `Empty`) tiles into connected regions, see [`flood_fill`](Self::flood_fill).
+	fn connected_components(&self, connectivity: Connectivity) -> Vec<FxHashSet<Point2>>;
+	/// Morphological dilation: returns map where pixels are `Set` if there's a `Set` pixel
+	/// in the square of given radius around them (i.e. obstacles grow and free area shrinks).
+	///
+	/// Dilating placement grid gives map of free tiles, where building can be centered
+	/// without overlapping obstacles (e.g. radius `1` for 3x3 buildings, `2` for 5x5 townhalls).
+	/// Pixels outside of the map are ignored.
+	fn dilate(&self, radius: u32) -> PixelMap;
+	/// Morphological erosion: returns map where pixels are `Set` only if all pixels
+	/// in the square of given radius around them are `Set` (i.e. obstacles shrink and free area grows).
+	///
+	/// Pixels outside of the map are ignored.
+	fn erode(&self, radius: u32) -> PixelMap;
+	/// Number of bits used to store one pixel in game's native format, always `1`.
+	fn bits_per_pixel(&self) -> u32 {
+		1
+	}
+	/// Packs map into game's native format, the same as in [`ImageData`] received from the API.
+	///
+	/// Pixels are stored row by row (starting from `y = 0`), 8 pixels per byte with the most significant bit first,
+	/// where `1` is `Empty` and `0` is `Set`. Rows aren't padded,
+	/// only unused bits of the last byte are filled with zeros.
+	///
+	/// Result can be converted back with [`TryFromProto`].
+	fn to_raw_bytes(&self) -> Vec<u8>;
+	/// Iterates over pixels of given row, yielding `true` for `Set` pixels.
+	///
+	/// # Panics
+	/// If `y` is out of bounds.
+	fn row(&self, y: usize) -> impl Iterator<Item = bool> + '_;
+	/// Converts map to grayscale image, where `Empty` pixels are white and `Set` pixels are black.
+	///
+	/// Image is flipped vertically, so it's oriented like in game (y axis points up).
+	#[cfg(feature = "image")]
+	fn to_image(&self) -> GrayImage;
+	/// Saves map to PNG file, see [`to_image`](Self::to_image).
+	#[cfg(feature = "image")]
+	fn save_png<P: AsRef<Path>>(&self, path: P) -> ImageResult<()> {
+		self.to_image().save_with_format(path, ImageFormat::Png)
+	}
+}
+impl PixelMapExt for PixelMap {
+	fn tiles_in_radius(&self, center: Point2, radius: f32) -> impl Iterator<Item = Point2> + '_ {
+		positions_in_radius(self, center, radius)
+			.filter(|pos| self[*pos].is_empty())
+			.map(Point2::from)
+	}
+	fn count_free_in_radius(&self, center: Point2, radius: f32) -> usize {
+		positions_in_radius(self, center, radius)
+			.filter(|pos| self[*pos].is_empty())
+			.count()
+	}
+	fn set_pixel(&mut self, pos: Point2, value: bool) {
+		if pos.x < 0.0 || pos.y < 0.0 {
+			return;
+		}
+		if let Some(pixel) = self.get_mut(<(usize, usize)>::from(pos)) {
+			*pixel = if value { Pixel::Set } else { Pixel::Empty };
+		}
+	}
+	fn get_pixel(&self, pos: Point2) -> Option<bool> {
+		if pos.x < 0.0 || pos.y < 0.0 {
+			return None;
+		}
+		self.get(<(usize, usize)>::from(pos)).map(|pixel| pixel.is_set())
+	}
+	fn union(&self, other: &PixelMap) -> PixelMap {
+		combine(self, other, |a, b| a || b)
+	}
+	fn intersect(&self, other: &PixelMap) -> PixelMap {
+		combine(self, other, |a, b| a && b)
+	}
+	fn difference(&self, other: &PixelMap) -> PixelMap {
+		combine(self, other, |a, b| a && !b)
+	}
+	fn flood_fill(&self, start: Point2, connectivity: Connectivity) -> FxHashSet<Point2> {
+		if start.x < 0.0 || start.y < 0.0 {
+			return FxHashSet::default();
+		}
+		let mut visited = Array2::from_elem(self.dim(), false);
+		flood_fill(self, start.to_tile(), connectivity, &mut visited)
+	}
+	fn connected_components(&self, connectivity: Connectivity) -> Vec<FxHashSet<Point2>> {
+		let mut visited = Array2::from_elem(self.dim(), false);
+		let (width, height) = self.dim();
+		iproduct!(0..width, 0..height)
+			.filter_map(|pos| {
+				if visited[pos] || self[pos].is_set() {
+					return None;
+				}
+				Some(flood_fill(self, pos, connectivity, &mut visited))
+			})
+			.collect()
+	}
+	fn dilate(&self, radius: u32) -> PixelMap {
+		grow(self, radius as usize, Pixel::Set)
+	}
+	fn erode(&self, radius: u32) -> PixelMap {
+		grow(self, radius as usize, Pixel::Empty)
+	}
+	fn to_raw_bytes(&self) -> Vec<u8> {
+		let (width, height) = self.dim();
+		let mut bytes = vec![0; (width * height).div_ceil(8)];
+		for (i, (y, x)) in iproduct!(0..height, 0..width).enumerate() {
+			if self[(x, y)].is_empty() {
+				bytes[i / 8] |= 0x80 >> (i % 8);
+			}
+		}
+		bytes
+	}
+	fn row(&self, y: usize) -> impl Iterator<Item = bool> + '_ {
+		self.column(y).into_iter().map(|pixel| pixel.is_set())
+	}
+	#[cfg(feature = "image")]
+	fn to_image(&self) -> GrayImage {
+		to_image(self, |pixel| if pixel.is_empty() { u8::MAX } else { 0 })
+	}
+}
+
+/// Spreads pixels with given value to all pixels within square of given radius.
+///
+/// Square window is separable, so it's done in two passes: along x and then along y.
+fn grow(map: &PixelMap, radius: usize, value: Pixel) -> PixelMap {
+	let (width, height) = map.dim();
+	let window = |i: usize, len: usize| i.saturating_sub(radius)..=(i + radius).min(len - 1);
+
+	let horizontal = Array2::from_shape_fn((width, height), |(x, y)| {
+		window(x, width).any(|i| map[(i, y)] == value)
+	});
+	Array2::from_shape_fn((width, height), |(x, y)| {
+		if window(y, height).any(|j| horizontal[(x, j)]) {
+			value
+		} else {
+			match value {
+				Pixel::Set => Pixel::Empty,
+				Pixel::Empty => Pixel::Set,
+			}
+		}
+	})
+}
+
+/// Which tiles are considered adjacent in [`flood_fill`](PixelMapExt::flood_fill).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+	/// Tiles adjacent by side.
+	Four,
+	/// Tiles adjacent by side or by corner.
+	Eight,
+}
+impl Connectivity {
+	fn offsets(self) -> &'static [(isize, isize)] {
+		const OFFSETS: [(isize, isize); 8] = [
+			(1, 0),
+			(-1, 0),
+			(0, 1),
+			(0, -1),
+			(1, 1),
+			(-1, -1),
+			(1, -1),
+			(-1, 1),
+		];
+		match self {
+			Self::Four => &OFFSETS[..4],
+			Self::Eight => &OFFSETS,
+		}
+	}
+}
+
+/// Collects free tiles connected to `start`, marking them as visited.
+///
+/// Uses explicit stack, since recursion may overflow on large maps.
+fn flood_fill(
+	map: &PixelMap,
+	start: (usize, usize),
+	connectivity: Connectivity,
+	visited: &mut Array2<bool>,
+) -> FxHashSet<Point2> {
+	let mut region = FxHashSet::default();
+	if !map.get(start).is_some_and(|pixel| pixel.is_empty()) || visited[start] {
+		return region;
+	}
+
+	visited[start] = true;
+	let mut stack = vec![start];
+	while let Some((x, y)) = stack.pop() {
+		region.insert(Point2::from((x, y)));
+		for &(dx, dy) in connectivity.offsets() {
+			let (Some(nx), Some(ny)) = (x.checked_add_signed(dx), y.checked_add_signed(dy)) else {
+				continue;
+			};
+			let next = (nx, ny);
+			if map.get(next).is_some_and(|pixel| pixel.is_empty()) && !visited[next] {
+				visited[next] = true;
+				stack.push(next);
+			}
+		}
+	}
+	region
+}
+
+/// Terrain height is encoded in a byte, where `0` is `-16.0` and `255` is `16.0` in world units.
+const HEIGHT_SCALE: f32 = 32.0 / 255.0;
+const HEIGHT_OFFSET: f32 = -16.0;
+/// Minimal height difference in world units for tile to be considered higher ground.
+/// Adjacent cliff levels differ by about 2 units, so ramps' slopes don't pass it.
+const HIGHER_GROUND_THRESHOLD: f32 = 1.5;
+
+/// Additional methods for [`ByteMap`].
+pub trait ByteMapExt {
+	/// Returns terrain height in world units at given position,
+	/// bilinearly interpolated between centers of four surrounding tiles.
+	///
+	/// Should be used only on terrain height map.
+	fn height_at(&self, pos: Point2) -> f32;
+	/// Returns centers of pathable tiles within `radius` of `p`,
+	/// which are noticeably higher than `p` (by at least one cliff level),
+	/// sorted by height descending.
+	///
+	/// Should be used only on terrain height map, `pathing` is used to filter out obstacles.
+	fn higher_ground_near(&self, pathing: &PixelMap, p: Point2, radius: f32) -> Vec<Point2>;
+	/// Converts map to grayscale image, where brightness of pixel is its value.
+	///
+	/// Image is flipped vertically, so it's oriented like in game (y axis points up).
+	#[cfg(feature = "image")]
+	fn to_image(&self) -> GrayImage;
+	/// Saves map to PNG file, see [`to_image`](Self::to_image).
+	#[cfg(feature = "image")]
+	fn save_png<P: AsRef<Path>>(&self, path: P) -> ImageResult<()> {
+		self.to_image().save_with_format(path, ImageFormat::Png)
+	}
+}
+impl ByteMapExt for ByteMap {
+	fn height_at(&self, pos: Point2) -> f32 {
+		let (width, height) = self.dim();
+		if width == 0 || height == 0 {
+			return 0.0;
+		}
+
+		let x = (pos.x - 0.5).clamp(0.0, (width - 1) as f32);
+		let y = (pos.y - 0.5).clamp(0.0, (height - 1) as f32);
+		let (x0, y0) = (x as usize, y as usize);
+		let (x1, y1) = ((x0 + 1).min(width - 1), (y0 + 1).min(height - 1));
+		let (fx, fy) = (x.fract(), y.fract());
+
+		let h = |x, y| self[(x, y)] as f32;
+		let bottom = h(x0, y0) * (1.0 - fx) + h(x1, y0) * fx;
+		let top = h(x0, y1) * (1.0 - fx) + h(x1, y1) * fx;
+		(bottom * (1.0 - fy) + top * fy) * HEIGHT_SCALE + HEIGHT_OFFSET
+	}
+	fn higher_ground_near(&self, pathing: &PixelMap, p: Point2, radius: f32) -> Vec<Point2> {
+		let min_height = self.height_at(p) + HIGHER_GROUND_THRESHOLD;
+		let mut tiles = positions_in_radius(self, p, radius)
+			.filter(|&pos| pathing.get(pos).is_some_and(|pixel| pixel.is_empty()))
+			.map(|pos| {
+				let pos = Point2::from(pos);
+				(pos, self.height_at(pos))
+			})
+			.filter(|&(_, height)| height >= min_height)
+			.collect::<Vec<_>>();
+		tiles.sort_unstable_by(|(_, a), (_, b)| b.total_cmp(a));
+		tiles.into_iter().map(|(pos, _)| pos).collect()
+	}
+	#[cfg(feature = "image")]
+	fn to_image(&self) -> GrayImage {
+		to_image(self, |value| *value)
+	}
+}
+
+#[cfg(feature = "image")]
+fn to_image<T>(map: &Array2<T>, f: impl Fn(&T) -> u8) -> GrayImage {
+	let (width, height) = map.dim();
+	GrayImage::from_fn(width as u32, height as u32, |x, y| {
+		Luma([f(&map[(x as usize, height - 1 - y as usize)])])
+	})
+}
+
+fn combine(a: &PixelMap, b: &PixelMap, f: impl Fn(bool, bool) -> bool) -> PixelMap {
+	assert_eq!(
+		a.dim(),
+		b.dim(),
+		"Can't combine PixelMaps with different dimensions"
+	);
+	Zip::from(a).and(b).map_collect(|a, b| {
+		if f(a.is_set(), b.is_set()) {
+			Pixel::Set
+		} else {
+			Pixel::Empty
+		}
+	})
+}
+
+/// Iterates over tiles with centers within given circle, clamped to bounds of given grid.
+pub(crate) fn positions_in_radius(
+	grid: &impl Grid,
+	center: Point2,
+	radius: f32,
+) -> impl Iterator<Item = (usize, usize)> {
+	let Size { x: width, y: height } = grid.size();
+	let clamp = |v: f32, max: usize| (v.max(0.0) as usize).min(max);
+	let x0 = clamp(center.x - radius, width);
+	let x1 = clamp(center.x + radius + 1.0, width);
+	let y0 = clamp(center.y - radius, height);
+	let y1 = clamp(center.y + radius + 1.0, height);
+	let radius_squared = radius * radius;
+
+	iproduct!(x0..x1, y0..y1).filter(move |&(x, y)| {
+		let dx = x as f32 + 0.5 - center.x;
+		let dy = y as f32 + 0.5 - center.y;
+		dx * dx + dy * dy <= radius_squared
+	})
+}
 
 fn to_binary(n: u8) -> impl Iterator<Item = Pixel> {
 	(0..8).rev().map(move |x| Pixel::from_u8((n >> x) & 1).unwrap())
 }
 
+/// Reason why map can't be constructed from [`ImageData`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ImageDataError {
+	BitsPerPixel {
+		expected: i32,
+		actual: i32,
+	},
+	Size {
+		width: i32,
+		height: i32,
+	},
+	Value(u8),
+	Data {
+		required_bits: usize,
+		actual_bits: usize,
+	},
+}
+impl fmt::Display for ImageDataError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::BitsPerPixel { expected, actual } => {
+				write!(f, "expected {} bits per pixel, got {}", expected, actual)
+			}
+			Self::Size { width, height } => write!(f, "invalid image size {}x{}", width, height),
+			Self::Value(value) => write!(f, "invalid value in image: {}", value),
+			Self::Data {
+				required_bits,
+				actual_bits,
+			} => write!(
+				f,
+				"image requires {} bits of data, got {}",
+				required_bits, actual_bits
+			),
+		}
+	}
+}
+
+/// Checks that image has given bits per pixel and enough data for its size.
+/// Returns image dimensions as `(width, height)`.
+fn check_image(grid: &ImageData, bits_per_pixel: i32) -> Result<(usize, usize), ImageDataError> {
+	if grid.bits_per_pixel() != bits_per_pixel {
+		return Err(ImageDataError::BitsPerPixel {
+			expected: bits_per_pixel,
+			actual: grid.bits_per_pixel(),
+		});
+	}
+	let size = grid.size.deref();
+	let (width, height) = (size.x(), size.y());
+	if width < 0 || height < 0 {
+		return Err(ImageDataError::Size { width, height });
+	}
+	let (width, height) = (width as usize, height as usize);
+	let required_bits = width * height * bits_per_pixel as usize;
+	let actual_bits = grid.data().len() * 8;
+	if actual_bits < required_bits {
+		return Err(ImageDataError::Data {
+			required_bits,
+			actual_bits,
+		});
+	}
+	Ok((width, height))
+}
+
+/// Converts row-major image data to map indexed by `(x, y)`.
+/// Data beyond image size is ignored.
+fn from_image_data<T>((width, height): (usize, usize), data: impl Iterator<Item = T>) -> Array2<T> {
+	Array2::from_shape_vec((height, width), data.take(width * height).collect())
+		.expect("Image data is checked to have enough values")
+		.reversed_axes()
+}
+
+fn pixel_map_from_image(grid: &ImageData) -> Result<PixelMap, ImageDataError> {
+	let dim = check_image(grid, 1)?;
+	Ok(from_image_data(
+		dim,
+		grid.data().iter().flat_map(|n| to_binary(*n)),
+	))
+}
+fn byte_map_from_image(grid: &ImageData) -> Result<ByteMap, ImageDataError> {
+	let dim = check_image(grid, 8)?;
+	Ok(from_image_data(dim, grid.data().iter().copied()))
+}
+fn visibility_map_from_image(grid: &ImageData) -> Result<VisibilityMap, ImageDataError> {
+	let dim = check_image(grid, 8)?;
+	let data = grid
+		.data()
+		.iter()
+		.map(|n| Visibility::from_u8(*n).ok_or(ImageDataError::Value(*n)))
+		.collect::<Result<Vec<_>, _>>()?;
+	Ok(from_image_data(dim, data.into_iter()))
+}
+
 impl FromProto<&ImageData> for PixelMap {
 	#[inline]
 	fn from_proto(grid: &ImageData) -> Self {
-		let size = grid.size.deref();
-		Array2::from_shape_vec(
-			(size.y() as usize, size.x() as usize),
-			grid.data().iter().flat_map(|n| to_binary(*n)).collect(),
-		)
-		.expect("Can't create PixelMap")
-		.reversed_axes()
+		pixel_map_from_image(grid).unwrap_or_else(|e| panic!("Can't create PixelMap: {}", e))
 	}
 }
 impl FromProto<&ImageData> for ByteMap {
 	#[inline]
 	fn from_proto(grid: &ImageData) -> Self {
-		let size = grid.size.deref();
-		Array2::from_shape_vec((size.y() as usize, size.x() as usize), grid.data().to_vec())
-			.expect("Can't create ByteMap")
-			.reversed_axes()
+		byte_map_from_image(grid).unwrap_or_else(|e| panic!("Can't create ByteMap: {}", e))
 	}
 }
 impl FromProto<&ImageData> for VisibilityMap {
 	#[inline]
 	fn from_proto(grid: &ImageData) -> Self {
-		let size = grid.size.deref();
-		Array2::from_shape_vec(
-			(size.y() as usize, size.x() as usize),
-			grid.data()
-				.iter()
-				.map(|n| {
-					Visibility::from_u8(*n)
-						.unwrap_or_else(|| panic!("enum Visibility has no variant with value: {}", n))
-				})
-				.collect(),
-		)
-		.expect("Can't create VisibilityMap")
-		.reversed_axes()
+		visibility_map_from_image(grid).unwrap_or_else(|e| panic!("Can't create VisibilityMap: {}", e))
+	}
+}
+
+/// Returns `None` and logs the reason when image is malformed.
+impl TryFromProto<&ImageData> for PixelMap {
+	fn try_from_proto(grid: &ImageData) -> Option<Self> {
+		pixel_map_from_image(grid)
+			.map_err(|e| warn!("Can't create PixelMap: {}", e))
+			.ok()
+	}
+}
+/// Returns `None` and logs the reason when image is malformed.
+impl TryFromProto<&ImageData> for ByteMap {
+	fn try_from_proto(grid: &ImageData) -> Option<Self> {
+		byte_map_from_image(grid)
+			.map_err(|e| warn!("Can't create ByteMap: {}", e))
+			.ok()
+	}
+}
+/// Returns `None` and logs the reason when image is malformed.
+impl TryFromProto<&ImageData> for VisibilityMap {
+	fn try_from_proto(grid: &ImageData) -> Option<Self> {
+		visibility_map_from_image(grid)
+			.map_err(|e| warn!("Can't create VisibilityMap: {}", e))
+			.ok()
 	}
 }
 
 /// Base for the most 2d maps.
 #[variant_checkers]
 #[derive(FromPrimitive, ToPrimitive, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Pixel {
 	/// When pixel is set, this tile is obstacle (e.g. not pathable | not placeable)
 	/// or has something on it (e.g. has creep).
@@ -100,6 +613,7 @@ impl fmt::Debug for Pixel {
 /// Base for visibility maps.
 #[variant_checkers]
 #[derive(Debug, FromPrimitive, ToPrimitive, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Visibility {
 	/// Position is hidden (i.e. weren't explored before)
 	#[default]
@@ -116,3 +630,57 @@ impl Visibility {
 		!matches!(self, Visibility::Hidden)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn pixel_access_ignores_negative_positions() {
+		let mut map = PixelMap::from_elem((4, 4), Pixel::Empty);
+		map.set_pixel(Point2::new(-3.0, 1.0), true);
+		assert_eq!(map.get_pixel(Point2::new(-3.0, 1.0)), None);
+		assert_eq!(map.get_pixel(Point2::new(1.0, -0.5)), None);
+		assert_eq!(map.get_pixel(Point2::new(0.0, 1.0)), Some(false));
+
+		map.set_pixel(Point2::new(0.5, 1.5), true);
+		assert_eq!(map.get_pixel(Point2::new(0.0, 1.0)), Some(true));
+	}
+
+	#[test]
+	fn radius_helpers_count_free_tiles() {
+		let mut map = PixelMap::from_elem((8, 8), Pixel::Empty);
+		map[(4, 4)] = Pixel::Set;
+		map[(3, 4)] = Pixel::Set;
+		let center = Point2::new(4.5, 4.5);
+
+		let free = map.tiles_in_radius(center, 1.5).collect::<Vec<_>>();
+		assert!(free.iter().all(|&p| map.get_pixel(p) == Some(false)));
+		assert_eq!(map.count_free_in_radius(center, 1.5), free.len());
+		assert_eq!(free.len(), 7);
+	}
+
+	fn image(width: i32, height: i32, bits_per_pixel: i32, data: Vec<u8>) -> ImageData {
+		let mut grid = ImageData::new();
+		grid.set_bits_per_pixel(bits_per_pixel);
+		let size = grid.size.mut_or_insert_default();
+		size.set_x(width);
+		size.set_y(height);
+		grid.set_data(data);
+		grid
+	}
+
+	#[test]
+	fn try_from_proto_checks_image() {
+		let mut map = PixelMap::from_elem((5, 3), Pixel::Empty);
+		map[(1, 0)] = Pixel::Set;
+		map[(4, 2)] = Pixel::Set;
+		let grid = image(5, 3, 1, map.to_raw_bytes());
+		assert!(PixelMap::try_from_proto(&grid) == Some(map));
+
+		assert!(PixelMap::try_from_proto(&image(5, 3, 8, vec![0; 15])).is_none());
+		assert!(PixelMap::try_from_proto(&image(5, 3, 1, vec![0])).is_none());
+		assert!(ByteMap::try_from_proto(&image(-1, 3, 8, vec![])).is_none());
+		assert!(VisibilityMap::try_from_proto(&image(1, 1, 8, vec![7])).is_none());
+	}
+}
@@ -1,7 +1,11 @@
 //! Items representing various player's data.
 #![allow(missing_docs)]
 
-use crate::{FromProto, IntoProto};
+use crate::{
+	FromProto, IntoProto,
+	consts::{RACE_VALUES, RaceValues},
+	ids::UnitTypeId,
+};
 use sc2_proto::{
 	common::Race as ProtoRace,
 	sc2api::{
@@ -27,6 +31,29 @@ pub enum Race {
 	#[default]
 	Random,
 }
+impl Race {
+	/// Returns [`RaceValues`] of this race, `None` for `Random`.
+	#[inline]
+	pub fn values(self) -> Option<&'static RaceValues> {
+		RACE_VALUES.get(&self)
+	}
+	/// Returns default townhall of this race, `None` for `Random`.
+	pub fn townhall(self) -> Option<UnitTypeId> {
+		self.values().map(|v| v.start_townhall)
+	}
+	/// Returns worker of this race, `None` for `Random`.
+	pub fn worker(self) -> Option<UnitTypeId> {
+		self.values().map(|v| v.worker)
+	}
+	/// Returns gas building of this race, `None` for `Random`.
+	pub fn gas_building(self) -> Option<UnitTypeId> {
+		self.values().map(|v| v.gas)
+	}
+	/// Returns supply provider of this race, `None` for `Random`.
+	pub fn supply_provider(self) -> Option<UnitTypeId> {
+		self.values().map(|v| v.supply)
+	}
+}
 impl FromProto<ProtoRace> for Race {
 	#[inline]
 	fn from_proto(race: ProtoRace) -> Self {
@@ -142,6 +169,7 @@ impl IntoProto<ProtoAIBuild> for AIBuild {
 
 /// Type of the player, used when joining a game.
 #[derive(Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PlayerType {
 	/// Bot or Human.
 	Participant,
@@ -172,6 +200,7 @@ impl IntoProto<ProtoPlayerType> for PlayerType {
 }
 
 /// Computer opponent configuration used in [`run_vs_computer`](crate::client::run_vs_computer).
+#[derive(Debug, Copy, Clone)]
 pub struct Computer {
 	pub race: Race,
 	pub difficulty: Difficulty,
@@ -208,3 +237,50 @@ impl FromProto<ProtoGameResult> for GameResult {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn race_units() {
+		let table = [
+			(
+				Race::Terran,
+				UnitTypeId::CommandCenter,
+				UnitTypeId::SCV,
+				UnitTypeId::Refinery,
+				UnitTypeId::SupplyDepot,
+			),
+			(
+				Race::Zerg,
+				UnitTypeId::Hatchery,
+				UnitTypeId::Drone,
+				UnitTypeId::Extractor,
+				UnitTypeId::Overlord,
+			),
+			(
+				Race::Protoss,
+				UnitTypeId::Nexus,
+				UnitTypeId::Probe,
+				UnitTypeId::Assimilator,
+				UnitTypeId::Pylon,
+			),
+		];
+		for (race, townhall, worker, gas_building, supply_provider) in table {
+			assert_eq!(race.townhall(), Some(townhall), "{:?}", race);
+			assert_eq!(race.worker(), Some(worker), "{:?}", race);
+			assert_eq!(race.gas_building(), Some(gas_building), "{:?}", race);
+			assert_eq!(race.supply_provider(), Some(supply_provider), "{:?}", race);
+		}
+	}
+
+	#[test]
+	fn random_race_has_no_units() {
+		assert!(Race::Random.values().is_none());
+		assert_eq!(Race::Random.townhall(), None);
+		assert_eq!(Race::Random.worker(), None);
+		assert_eq!(Race::Random.gas_building(), None);
+		assert_eq!(Race::Random.supply_provider(), None);
+	}
+}
@@ -97,6 +97,26 @@ impl Ramp {
 			Some((x / ps.len(), y / ps.len()))
 		}
 	}
+	/// Returns centers of upper tiles of the ramp.
+	pub fn upper_tiles(&self) -> Vec<Point2> {
+		self.upper().into_iter().map(Point2::from).collect()
+	}
+	/// Returns centers of lower tiles of the ramp.
+	pub fn lower_tiles(&self) -> Vec<Point2> {
+		self.lower().into_iter().map(Point2::from).collect()
+	}
+	/// Returns exact center of upper tiles of the ramp.
+	pub fn top(&self) -> Option<Point2> {
+		tiles_center(&self.upper())
+	}
+	/// Returns exact center of lower tiles of the ramp.
+	pub fn bottom(&self) -> Option<Point2> {
+		tiles_center(&self.lower())
+	}
+	/// Returns exact center of all tiles of the ramp.
+	pub fn center(&self) -> Option<Point2> {
+		tiles_center(&self.points)
+	}
 	fn upper2_for_ramp_wall(&self) -> Option<[Pos; 2]> {
 		let mut upper = self.upper();
 		if upper.len() > 5 {
@@ -212,6 +232,14 @@ impl Ramp {
 		Some(depots[0] - direction)
 	}
 }
+fn tiles_center(ps: &[Pos]) -> Option<Point2> {
+	if ps.is_empty() {
+		None
+	} else {
+		Some(ps.iter().copied().map(Point2::from).sum::<Point2>() / ps.len() as f32)
+	}
+}
+
 impl fmt::Debug for Ramp {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		write!(f, "Ramp({:?})", self.points)
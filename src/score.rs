@@ -1,12 +1,24 @@
 //! SC2 Score interface.
 
-use std::ops::Deref;
+use std::{
+	collections::VecDeque,
+	ops::{Deref, Sub},
+};
+#[cfg(feature = "serde")]
+use std::{
+	fs::{File, OpenOptions},
+	io::{self, BufWriter, Write},
+	path::Path,
+};
 
 use crate::{FromProto, IntoSC2};
 use sc2_proto::score::{CategoryScoreDetails, Score as ProtoScore, VitalScoreDetails, score};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 #[variant_checkers]
 #[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ScoreType {
 	#[default]
 	Curriculum,
@@ -22,7 +34,8 @@ impl FromProto<score::ScoreType> for ScoreType {
 	}
 }
 
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Category {
 	pub none: f32,
 	pub army: f32,
@@ -42,8 +55,23 @@ impl FromProto<&CategoryScoreDetails> for Category {
 		}
 	}
 }
+impl Sub for Category {
+	type Output = Self;
 
-#[derive(Default, Clone)]
+	#[inline]
+	fn sub(self, other: Self) -> Self::Output {
+		Self {
+			none: self.none - other.none,
+			army: self.army - other.army,
+			economy: self.economy - other.economy,
+			technology: self.technology - other.technology,
+			upgrade: self.upgrade - other.upgrade,
+		}
+	}
+}
+
+#[derive(Default, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Vital {
 	pub life: f32,
 	pub shields: f32,
@@ -59,11 +87,24 @@ impl FromProto<&VitalScoreDetails> for Vital {
 		}
 	}
 }
+impl Sub for Vital {
+	type Output = Self;
+
+	#[inline]
+	fn sub(self, other: Self) -> Self::Output {
+		Self {
+			life: self.life - other.life,
+			shields: self.shields - other.shields,
+			energy: self.energy - other.energy,
+		}
+	}
+}
 
 /// All kinds of scores stored here.
 ///
 /// Can be accessed through [state.observation.score](crate::game_state::Observation::score).
 #[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Score {
 	pub score_type: ScoreType,
 	pub total_score: i32,
@@ -97,6 +138,73 @@ pub struct Score {
 	pub current_apm: f32,
 	pub current_effective_apm: f32,
 }
+impl Score {
+	/// Serializes score to JSON string.
+	#[cfg(feature = "serde")]
+	pub fn to_json(&self) -> String {
+		serde_json::to_string(self).expect("Score contains only numbers, so it's always serializable")
+	}
+	/// Returns difference between this and previous score, e.g. resources collected since then.
+	///
+	/// Score type is taken from this score.
+	pub fn delta(&self, previous: &Score) -> Score {
+		Score {
+			score_type: self.score_type.clone(),
+			total_score: self.total_score - previous.total_score,
+			idle_production_time: self.idle_production_time - previous.idle_production_time,
+			idle_worker_time: self.idle_worker_time - previous.idle_worker_time,
+			total_value_units: self.total_value_units - previous.total_value_units,
+			total_value_structures: self.total_value_structures - previous.total_value_structures,
+			killed_value_units: self.killed_value_units - previous.killed_value_units,
+			killed_value_structures: self.killed_value_structures - previous.killed_value_structures,
+			collected_minerals: self.collected_minerals - previous.collected_minerals,
+			collected_vespene: self.collected_vespene - previous.collected_vespene,
+			collection_rate_minerals: self.collection_rate_minerals - previous.collection_rate_minerals,
+			collection_rate_vespene: self.collection_rate_vespene - previous.collection_rate_vespene,
+			spent_minerals: self.spent_minerals - previous.spent_minerals,
+			spent_vespene: self.spent_vespene - previous.spent_vespene,
+			food_used: self.food_used - previous.food_used,
+			killed_minerals: self.killed_minerals - previous.killed_minerals,
+			killed_vespene: self.killed_vespene - previous.killed_vespene,
+			lost_minerals: self.lost_minerals - previous.lost_minerals,
+			lost_vespene: self.lost_vespene - previous.lost_vespene,
+			friendly_fire_minerals: self.friendly_fire_minerals - previous.friendly_fire_minerals,
+			friendly_fire_vespene: self.friendly_fire_vespene - previous.friendly_fire_vespene,
+			used_minerals: self.used_minerals - previous.used_minerals,
+			used_vespene: self.used_vespene - previous.used_vespene,
+			total_used_minerals: self.total_used_minerals - previous.total_used_minerals,
+			total_used_vespene: self.total_used_vespene - previous.total_used_vespene,
+			total_damage_dealt: self.total_damage_dealt - previous.total_damage_dealt,
+			total_damage_taken: self.total_damage_taken - previous.total_damage_taken,
+			total_healed: self.total_healed - previous.total_healed,
+			current_apm: self.current_apm - previous.current_apm,
+			current_effective_apm: self.current_effective_apm - previous.current_effective_apm,
+		}
+	}
+	/// Part of collected minerals which was spent, `0` if nothing was collected.
+	pub fn mineral_efficiency(&self) -> f32 {
+		if self.collected_minerals > 0.0 {
+			self.spent_minerals / self.collected_minerals
+		} else {
+			0.0
+		}
+	}
+	/// Ratio of idle production time to given elapsed game time (in seconds),
+	/// `None` if no time elapsed.
+	///
+	/// Idle time is summed across all production structures,
+	/// so result is roughly average number of idle structures and may be bigger than `1`.
+	pub fn idle_fraction(&self, elapsed: f32) -> Option<f32> {
+		(elapsed > 0.0).then(|| self.idle_production_time / elapsed)
+	}
+	/// Ratio of resources (minerals and vespene) used on army to resources used on economy,
+	/// `None` if nothing was used on economy.
+	pub fn army_value_ratio(&self) -> Option<f32> {
+		let army = self.total_used_minerals.army + self.total_used_vespene.army;
+		let economy = self.total_used_minerals.economy + self.total_used_vespene.economy;
+		(economy > 0.0).then(|| army / economy)
+	}
+}
 impl FromProto<&ProtoScore> for Score {
 	#[inline]
 	fn from_proto(score: &ProtoScore) -> Self {
@@ -135,3 +243,114 @@ impl FromProto<&ProtoScore> for Score {
 		}
 	}
 }
+
+/// Bounded history of scores with game loops they were recorded on.
+///
+/// When history is full, the oldest score is removed on every [`push`](Self::push).
+#[derive(Clone)]
+pub struct ScoreHistory {
+	entries: VecDeque<(u32, Score)>,
+	capacity: usize,
+}
+impl ScoreHistory {
+	/// Constructs new history which keeps up to `capacity` latest scores.
+	/// With zero capacity nothing is recorded.
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			entries: VecDeque::with_capacity(capacity),
+			capacity,
+		}
+	}
+	/// Records score on given game loop. Loops are expected to be pushed in increasing order.
+	pub fn push(&mut self, game_loop: u32, score: Score) {
+		if self.capacity == 0 {
+			return;
+		}
+		if self.entries.len() == self.capacity {
+			self.entries.pop_front();
+		}
+		self.entries.push_back((game_loop, score));
+	}
+	/// Returns the latest recorded score with its game loop.
+	pub fn latest(&self) -> Option<(u32, &Score)> {
+		self.entries.back().map(|(game_loop, score)| (*game_loop, score))
+	}
+	/// Returns the latest score recorded on or before given game loop.
+	pub fn at_loop(&self, game_loop: u32) -> Option<&Score> {
+		let i = self.entries.partition_point(|(l, _)| *l <= game_loop);
+		i.checked_sub(1).map(|i| &self.entries[i].1)
+	}
+	/// Iterates over recorded scores with their game loops from oldest to latest.
+	pub fn iter(&self) -> impl DoubleEndedIterator<Item = (u32, &Score)> + ExactSizeIterator {
+		self.entries.iter().map(|(game_loop, score)| (*game_loop, score))
+	}
+	/// Iterates over up to `last_n` latest scores from oldest to latest.
+	pub fn window(
+		&self,
+		last_n: usize,
+	) -> impl DoubleEndedIterator<Item = (u32, &Score)> + ExactSizeIterator {
+		self.iter().skip(self.entries.len().saturating_sub(last_n))
+	}
+	/// Number of recorded scores.
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+	/// Checks if no scores were recorded.
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+	/// Maximum number of scores kept.
+	pub fn capacity(&self) -> usize {
+		self.capacity
+	}
+}
+
+/// Writes scores to file as newline-delimited JSON, one line per logged step.
+///
+/// Each line is object with `game_loop` and `score` fields, which is easy to stream into external tools.
+/// Writes are buffered and flushed every [`flush_interval`](Self::with_flush_interval) entries
+/// (every entry by default) and when logger is dropped.
+#[cfg(feature = "serde")]
+pub struct ScoreLogger {
+	writer: BufWriter<File>,
+	flush_interval: u32,
+	unflushed: u32,
+}
+#[cfg(feature = "serde")]
+impl ScoreLogger {
+	/// Opens file at given path for appending, creating it if it doesn't exist.
+	pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+		let file = OpenOptions::new().create(true).append(true).open(path)?;
+		Ok(Self {
+			writer: BufWriter::new(file),
+			flush_interval: 1,
+			unflushed: 0,
+		})
+	}
+	/// Sets number of entries written between flushes (`0` is treated as `1`).
+	pub fn with_flush_interval(mut self, entries: u32) -> Self {
+		self.flush_interval = entries.max(1);
+		self
+	}
+	/// Writes score recorded on given game loop.
+	pub fn log(&mut self, game_loop: u32, score: &Score) -> io::Result<()> {
+		#[derive(Serialize)]
+		struct Entry<'a> {
+			game_loop: u32,
+			score: &'a Score,
+		}
+
+		serde_json::to_writer(&mut self.writer, &Entry { game_loop, score })?;
+		self.writer.write_all(b"\n")?;
+		self.unflushed += 1;
+		if self.unflushed >= self.flush_interval {
+			self.flush()?;
+		}
+		Ok(())
+	}
+	/// Writes all buffered entries to the file.
+	pub fn flush(&mut self) -> io::Result<()> {
+		self.unflushed = 0;
+		self.writer.flush()
+	}
+}
@@ -0,0 +1,120 @@
+//! Spatial map of threat (or any other influence) over map tiles.
+
+use crate::{
+	distance::Distance,
+	geometry::{Point2, Size},
+	pixel_map::{Grid, PixelMap, positions_in_radius},
+};
+use ndarray::Array2;
+
+/// How value of threat source decreases with distance from its center.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Falloff {
+	/// Full weight in the whole radius.
+	Constant,
+	/// Weight decreases linearly from full in center to `0` on the edge of radius.
+	#[default]
+	Linear,
+	/// Weight decreases by gaussian curve with standard deviation of half radius,
+	/// cut off on the edge of radius.
+	Gaussian,
+}
+impl Falloff {
+	fn factor(self, distance: f32, radius: f32) -> f32 {
+		match self {
+			Self::Constant => 1.0,
+			Self::Linear => 1.0 - distance / radius,
+			Self::Gaussian => {
+				let sigma = radius / 2.0;
+				(-(distance * distance) / (2.0 * sigma * sigma)).exp()
+			}
+		}
+	}
+}
+
+/// Float grid where threat of multiple sources is summed up on each tile.
+///
+/// Usually built every step from enemy units, e.g. with their weapon ranges as radius and dps as weight,
+/// and then used to find safe positions for your units.
+///
+/// Usage:
+/// ```ignore
+/// let mut threat = ThreatMap::new(&self.game_info.pathing_grid);
+/// for u in &self.units.enemy.units {
+/// 	threat.add_source(u.position(), u.real_ground_range() + 1.0, u.ground_dps());
+/// }
+/// let retreat = threat.safest_point_near(unit.position(), 5.0);
+/// ```
+#[derive(Clone)]
+pub struct ThreatMap {
+	values: Array2<f32>,
+	pathing: PixelMap,
+	/// Falloff used by new sources. [Default: [`Linear`](Falloff::Linear)]
+	pub falloff: Falloff,
+}
+impl ThreatMap {
+	/// Constructs empty map with the same size as given pathing grid,
+	/// which is used to find safe points.
+	pub fn new(pathing_grid: &PixelMap) -> Self {
+		Self {
+			values: Array2::zeros(pathing_grid.dim()),
+			pathing: pathing_grid.clone(),
+			falloff: Falloff::default(),
+		}
+	}
+	/// Sets falloff used by new sources.
+	pub fn with_falloff(mut self, falloff: Falloff) -> Self {
+		self.falloff = falloff;
+		self
+	}
+	/// Adds threat with given weight to tiles within radius around given position.
+	pub fn add_source(&mut self, pos: Point2, radius: f32, weight: f32) {
+		if radius <= 0.0 {
+			return;
+		}
+		let falloff = self.falloff;
+		let values = &mut self.values;
+		for tile in positions_in_radius(&self.pathing, pos, radius) {
+			let distance = Point2::from(tile).distance(pos);
+			values[tile] += weight * falloff.factor(distance, radius);
+		}
+	}
+	/// Returns threat on tile containing given point, `0` if it's out of bounds.
+	pub fn value_at(&self, p: Point2) -> f32 {
+		if !self.in_bounds(p) {
+			return 0.0;
+		}
+		self.values[p.to_tile()]
+	}
+	/// Returns center of pathable tile with the lowest threat within given radius around `center`,
+	/// or `None` if there're no pathable tiles in this area.
+	///
+	/// Among tiles with equal threat the closest to `center` is chosen.
+	pub fn safest_point_near(&self, center: Point2, search_radius: f32) -> Option<Point2> {
+		positions_in_radius(&self.pathing, center, search_radius)
+			.filter(|&tile| self.pathing[tile].is_empty())
+			.map(|tile| {
+				let p = Point2::from(tile);
+				(p, self.values[tile], p.distance_squared(center))
+			})
+			.min_by(|(_, a, da), (_, b, db)| a.total_cmp(b).then(da.total_cmp(db)))
+			.map(|(p, _, _)| p)
+	}
+	/// Resets threat on all tiles to `0`.
+	pub fn clear(&mut self) {
+		self.values.fill(0.0);
+	}
+}
+impl Grid for ThreatMap {
+	type Value = f32;
+
+	#[inline]
+	fn size(&self) -> Size {
+		let (x, y) = self.values.dim();
+		Size::new(x, y)
+	}
+	#[inline]
+	fn cell(&self, x: usize, y: usize) -> Option<Self::Value> {
+		self.values.get((x, y)).copied()
+	}
+}
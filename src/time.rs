@@ -0,0 +1,57 @@
+//! Conversions between game loops and seconds.
+
+use crate::consts::{FRAMES_PER_SECOND, GAME_SPEED};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Duration measured in game loops.
+///
+/// Game data reports all times ([`Cost::time`], [`UnitTypeData::build_time`],
+/// [`UpgradeData::research_time`]) in game loops, which run at [`FRAMES_PER_SECOND`] on **Faster** speed.
+/// This type converts them to seconds in one place:
+/// - [`as_seconds`](Self::as_seconds) gives real seconds on **Faster**, as shown by in-game clock
+/// and [`time`](crate::bot::Bot::time) field;
+/// - [`as_game_seconds`](Self::as_game_seconds) gives seconds on **Normal** speed,
+/// units in which [`Weapon::speed`] and other editor values are measured.
+///
+/// [`Cost::time`]: crate::game_data::Cost::time
+/// [`UnitTypeData::build_time`]: crate::game_data::UnitTypeData::build_time
+/// [`UpgradeData::research_time`]: crate::game_data::UpgradeData::research_time
+/// [`Weapon::speed`]: crate::game_data::Weapon::speed
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameTime(u32);
+impl GameTime {
+	/// Creates time from given number of game loops.
+	#[inline]
+	pub fn from_loops(loops: u32) -> Self {
+		Self(loops)
+	}
+	/// Creates time from given number of real seconds on **Faster** speed, rounded to the nearest loop.
+	#[inline]
+	pub fn from_seconds(seconds: f32) -> Self {
+		Self((seconds * FRAMES_PER_SECOND).round() as u32)
+	}
+	/// Returns number of game loops.
+	#[inline]
+	pub fn loops(self) -> u32 {
+		self.0
+	}
+	/// Returns real seconds on **Faster** speed.
+	#[inline]
+	pub fn as_seconds(self) -> f32 {
+		self.0 as f32 / FRAMES_PER_SECOND
+	}
+	/// Returns seconds on **Normal** speed (i.e. real seconds multiplied by [`GAME_SPEED`]).
+	#[inline]
+	pub fn as_game_seconds(self) -> f32 {
+		self.as_seconds() * GAME_SPEED
+	}
+}
+impl From<u32> for GameTime {
+	#[inline]
+	fn from(loops: u32) -> Self {
+		Self(loops)
+	}
+}
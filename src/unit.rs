@@ -767,8 +767,7 @@ impl Unit {
 	}
 	/// Checks if unit has given attribute.
 	pub fn has_attribute(&self, attribute: Attribute) -> bool {
-		self.type_data()
-			.is_some_and(|data| data.attributes.contains(&attribute))
+		self.type_data().is_some_and(|data| data.has_attribute(attribute))
 	}
 	/// Checks if unit has `Light` attribute.
 	pub fn is_light(&self) -> bool {
@@ -945,14 +944,8 @@ impl Unit {
 		if target.type_id() == UnitTypeId::Colossus {
 			!weapons.is_empty()
 		} else {
-			let not_target = {
-				if target.is_flying() {
-					TargetType::Ground
-				} else {
-					TargetType::Air
-				}
-			};
-			weapons.iter().any(|w| w.target != not_target)
+			let flying = target.is_flying();
+			weapons.iter().any(|w| w.target.covers(flying))
 		}
 	}
 	/// Checks if unit's weapon is on cooldown.